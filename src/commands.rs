@@ -0,0 +1,376 @@
+//! Non-interactive subcommands.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{Local, Utc};
+use serde::Serialize;
+use success_core::app::AppState;
+use success_core::config::Config;
+use success_core::handlers::load_day;
+use success_core::utils::{
+    day_summary_markdown, format_quantity_totals, format_secs_short, parse_relative_date,
+    quantity_totals,
+};
+use successlib::{Goal, SessionKind};
+
+use crate::timer_file;
+
+#[derive(Debug, Serialize)]
+struct StatusOutput {
+    active: bool,
+    label: Option<String>,
+    goal_id: Option<u64>,
+    is_reward: Option<bool>,
+    remaining: Option<u64>,
+}
+
+/// Print the running timer as one line of JSON, or using `format` where
+/// `{label}`, `{remaining}` (seconds) and `{mmss}` are substituted.
+pub fn status(archive: &Path, format: Option<&str>) -> Result<()> {
+    let active = timer_file::read(archive)
+        .and_then(|timer| timer.remaining(Utc::now()).map(|left| (timer, left)));
+
+    if let Some(format) = format {
+        let line = match &active {
+            Some((timer, left)) => format
+                .replace("{label}", &timer.label)
+                .replace("{remaining}", &left.to_string())
+                .replace("{mmss}", &format!("{:02}:{:02}", left / 60, left % 60)),
+            None => String::new(),
+        };
+        println!("{line}");
+        return Ok(());
+    }
+
+    let output = match active {
+        Some((timer, left)) => StatusOutput {
+            active: true,
+            label: Some(timer.label),
+            goal_id: Some(timer.goal_id),
+            is_reward: Some(timer.is_reward),
+            remaining: Some(left),
+        },
+        None => StatusOutput {
+            active: false,
+            label: None,
+            goal_id: None,
+            is_reward: None,
+            remaining: None,
+        },
+    };
+    println!("{}", serde_json::to_string(&output)?);
+    Ok(())
+}
+
+/// Print `name<TAB>id` for each goal, optionally only rewards or only goals.
+pub fn goals(archive: &Path, is_reward: Option<bool>) -> Result<()> {
+    let archive = archive.to_string_lossy().to_string();
+    let goals = match is_reward {
+        Some(is_reward) => {
+            successlib::search_goals(archive, String::new(), Some(is_reward), None, Some(true))
+        }
+        None => successlib::list_goals(archive, None),
+    }
+    .unwrap_or_default();
+    for goal in goals {
+        println!("{}\t{}", goal.name, goal.id);
+    }
+    Ok(())
+}
+
+/// Two goals whose commands overlap, so ending one can `pkill -f` the other's apps.
+#[derive(Debug)]
+pub struct CommandOverlap {
+    pub first: String,
+    pub second: String,
+    pub first_command: String,
+    pub second_command: String,
+}
+
+/// Pair each of a goal's commands with the seconds to wait before starting
+/// it. A `sleep N` entry delays the commands after it and `delay=N cmd` delays
+/// `cmd` and those after it, so apps can be opened in order. A `delay=`
+/// entry without a command or a valid number is ignored.
+pub fn plan_commands(commands: &[String]) -> Vec<(u64, String)> {
+    let mut delay = 0;
+    let mut planned = Vec::new();
+    for cmd in commands {
+        let cmd = cmd.trim();
+        if let Some(secs) = cmd
+            .strip_prefix("sleep ")
+            .and_then(|secs| secs.trim().parse::<u64>().ok())
+        {
+            delay += secs;
+            continue;
+        }
+        let cmd = match cmd.strip_prefix("delay=") {
+            Some(rest) => {
+                let Some((secs, rest)) = rest
+                    .split_once(char::is_whitespace)
+                    .and_then(|(secs, rest)| Some((secs.parse::<u64>().ok()?, rest.trim())))
+                    .filter(|(_, rest)| !rest.is_empty())
+                else {
+                    continue;
+                };
+                delay += secs;
+                rest
+            }
+            None => cmd,
+        };
+        planned.push((delay, cmd.to_string()));
+    }
+    planned
+}
+
+/// Find commands of different goals where one contains the other. Apps are
+/// killed with `pkill -f <command>`, which matches such overlaps too.
+pub fn find_command_overlaps(goals: &[Goal]) -> Vec<CommandOverlap> {
+    let commands = |goal: &Goal| -> Vec<String> {
+        plan_commands(&goal.commands)
+            .into_iter()
+            .map(|(_, cmd)| cmd)
+            .collect()
+    };
+    let mut overlaps = Vec::new();
+    for (i, a) in goals.iter().enumerate() {
+        for b in goals.iter().skip(i + 1) {
+            for ca in &commands(a) {
+                for cb in &commands(b) {
+                    if ca.contains(cb.as_str()) || cb.contains(ca.as_str()) {
+                        overlaps.push(CommandOverlap {
+                            first: a.name.clone(),
+                            second: b.name.clone(),
+                            first_command: ca.clone(),
+                            second_command: cb.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    overlaps
+}
+
+/// Report setup problems, currently goals whose commands can kill each other.
+pub fn doctor(archive: &Path) -> Result<()> {
+    let goals =
+        successlib::list_goals(archive.to_string_lossy().to_string(), None).unwrap_or_default();
+    let overlaps = find_command_overlaps(&goals);
+    if overlaps.is_empty() {
+        println!("No problems found.");
+        return Ok(());
+    }
+    for o in &overlaps {
+        println!(
+            "warning: '{}' ({}) and '{}' ({}) have overlapping commands; ending one may close the other's apps",
+            o.first, o.first_command, o.second, o.second_command
+        );
+    }
+    println!(
+        "Consider making the commands distinct or disabling kill_on_end for one of the goals."
+    );
+    Ok(())
+}
+
+/// Write a Markdown summary of `day` (default today) to
+/// `summaries/YYYY-MM-DD.md` in the archive and print its path.
+pub fn export_daily(archive: &Path, day: Option<&str>, config: &Config) -> Result<()> {
+    let today = Local::now().date_naive();
+    let day = match day {
+        Some(input) => parse_relative_date(input, today)
+            .with_context(|| format!("Unrecognised date: {input}"))?,
+        None => today,
+    };
+    let mut app = AppState::new(archive.to_string_lossy().to_string());
+    app.config = config.clone();
+    load_day(&mut app, day);
+
+    let dir = archive.join("summaries");
+    fs::create_dir_all(&dir).with_context(|| format!("Creating {}", dir.display()))?;
+    let path = dir.join(format!("{}.md", day.format("%Y-%m-%d")));
+    fs::write(&path, day_summary_markdown(&app))
+        .with_context(|| format!("Writing {}", path.display()))?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct TotalOutput {
+    from: String,
+    to: String,
+    goal: Option<String>,
+    sessions: usize,
+    goal_secs: i64,
+    reward_secs: i64,
+    /// Quantity totals per unit, only with `--goal`.
+    quantities: Vec<(String, i64)>,
+}
+
+/// Print the goal and reward time recorded between `from` and `to`
+/// (inclusive, local days), optionally only for the goal named `goal`.
+pub fn total(archive: &Path, from: &str, to: &str, goal: Option<&str>, json: bool) -> Result<()> {
+    let today = Local::now().date_naive();
+    let parse = |input: &str| {
+        parse_relative_date(input, today).with_context(|| format!("Unrecognised date: {input}"))
+    };
+    let (from, to) = (parse(from)?, parse(to)?);
+    if from > to {
+        bail!("--from {from} is after --to {to}");
+    }
+    let app = AppState::new(archive.to_string_lossy().to_string());
+    let goal = match goal {
+        Some(name) => Some(
+            app.goals
+                .iter()
+                .find(|g| g.name.eq_ignore_ascii_case(name))
+                .with_context(|| format!("No goal named {name:?}"))?,
+        ),
+        None => None,
+    };
+
+    let sessions: Vec<_> =
+        successlib::list_sessions_between_dates(app.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| goal.is_none_or(|g| g.id == s.goal_id))
+            .filter(|s| {
+                chrono::DateTime::from_timestamp(s.start_at, 0)
+                    .map(|dt| dt.with_timezone(&Local).date_naive())
+                    .is_some_and(|day| day >= from && day <= to)
+            })
+            .collect();
+    let secs = |kind: SessionKind| -> i64 {
+        sessions
+            .iter()
+            .filter(|s| s.kind == kind)
+            .map(|s| s.end_at - s.start_at)
+            .sum()
+    };
+    let output = TotalOutput {
+        from: from.to_string(),
+        to: to.to_string(),
+        goal: goal.map(|g| g.name.clone()),
+        sessions: sessions.len(),
+        goal_secs: secs(SessionKind::Goal),
+        reward_secs: secs(SessionKind::Reward),
+        quantities: if goal.is_some() {
+            quantity_totals(&app, &sessions)
+        } else {
+            Vec::new()
+        },
+    };
+
+    if json {
+        println!("{}", serde_json::to_string(&output)?);
+        return Ok(());
+    }
+    let scope = output.goal.as_deref().unwrap_or("All goals");
+    println!("{scope}, {} to {}", output.from, output.to);
+    println!("Sessions: {}", output.sessions);
+    println!("Goal time: {}", format_secs_short(output.goal_secs));
+    println!("Reward time: {}", format_secs_short(output.reward_secs));
+    if !output.quantities.is_empty() {
+        println!("Quantities: {}", format_quantity_totals(&output.quantities));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(commands: &[&str]) -> Vec<(u64, String)> {
+        plan_commands(&commands.iter().map(|c| c.to_string()).collect::<Vec<_>>())
+    }
+
+    /// Goals with the given names and commands, added to a fresh archive.
+    fn goals(test: &str, specs: &[(&str, Vec<&str>)]) -> (std::path::PathBuf, Vec<Goal>) {
+        let archive = std::env::temp_dir().join(format!(
+            "success-cli-{test}-{}-{}",
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&archive).unwrap();
+        let goals = specs
+            .iter()
+            .map(|(name, commands)| {
+                successlib::add_goal(
+                    archive.to_string_lossy().to_string(),
+                    name.to_string(),
+                    false,
+                    commands.iter().map(|c| c.to_string()).collect(),
+                    None,
+                )
+                .expect("Failed to add goal")
+            })
+            .collect();
+        (archive, goals)
+    }
+
+    #[test]
+    fn overlaps_match_substrings_either_way() {
+        let (archive, goals) = goals(
+            "overlaps",
+            &[
+                ("Write", vec!["code ~/book"]),
+                ("Code", vec!["code"]),
+                ("Music", vec!["spotify"]),
+                ("Focus", vec!["spotify --minimized"]),
+            ],
+        );
+        let overlaps = find_command_overlaps(&goals);
+        let pairs: Vec<(&str, &str)> = overlaps
+            .iter()
+            .map(|o| (o.first_command.as_str(), o.second_command.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("code ~/book", "code"), ("spotify", "spotify --minimized")]
+        );
+        let _ = fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn overlaps_within_one_goal_are_ignored() {
+        let (archive, goals) = goals("own-overlaps", &[("Write", vec!["code", "code ~/book"])]);
+        assert!(find_command_overlaps(&goals).is_empty());
+        let _ = fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn sleep_entries_delay_later_commands() {
+        assert_eq!(
+            plan(&["editor", "sleep 3", "browser"]),
+            vec![(0, "editor".to_string()), (3, "browser".to_string())]
+        );
+    }
+
+    #[test]
+    fn delay_prefixes_accumulate() {
+        assert_eq!(
+            plan(&[
+                "delay=2 editor",
+                "browser",
+                "delay=5  music --shuffle",
+                "sleep 1",
+                "notes"
+            ]),
+            vec![
+                (2, "editor".to_string()),
+                (2, "browser".to_string()),
+                (7, "music --shuffle".to_string()),
+                (8, "notes".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn delay_without_a_command_is_ignored() {
+        assert_eq!(
+            plan(&["delay=2", "delay=x editor", "browser"]),
+            vec![(0, "browser".to_string())]
+        );
+    }
+}
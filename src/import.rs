@@ -0,0 +1,289 @@
+//! Importing sessions from other time trackers' CSV exports.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use clap::ValueEnum;
+use success_core::utils::parse_duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// Columns `goal`, `start` ("YYYY-MM-DD HH:MM") and `duration` (e.g. "1h 30m")
+    Csv,
+    /// Toggl Track's detailed CSV export
+    Toggl,
+}
+
+/// Where a format keeps each session field. The start may be split over a
+/// date and a time column.
+struct ColumnMapping {
+    goal: &'static str,
+    start_date: &'static str,
+    start_time: Option<&'static str>,
+    duration: &'static str,
+}
+
+impl ImportFormat {
+    fn mapping(self) -> ColumnMapping {
+        match self {
+            ImportFormat::Csv => ColumnMapping {
+                goal: "goal",
+                start_date: "start",
+                start_time: None,
+                duration: "duration",
+            },
+            ImportFormat::Toggl => ColumnMapping {
+                goal: "Description",
+                start_date: "Start date",
+                start_time: Some("Start time"),
+                duration: "Duration",
+            },
+        }
+    }
+}
+
+/// One session read from an export, not yet matched to a goal.
+struct ImportedSession {
+    line: usize,
+    goal: String,
+    start: i64,
+    duration_secs: u32,
+}
+
+/// Split CSV `content` into records, handling quoted fields with embedded
+/// commas, newlines and doubled quotes. Each record comes with the line of
+/// the file it starts on.
+fn parse_csv(content: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => in_quotes = !in_quotes,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push((record_line, record));
+    }
+    records
+}
+
+/// Parse "HH:MM:SS" (Toggl) or a duration like "1h 30m".
+fn parse_session_duration(value: &str) -> Option<u64> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+    if let [h, m, s] = parts.as_slice() {
+        return Some(
+            h.parse::<u64>().ok()? * 3600 + m.parse::<u64>().ok()? * 60 + s.parse::<u64>().ok()?,
+        );
+    }
+    parse_duration(value)
+}
+
+fn parse_start(date: &str, time: Option<&str>) -> Option<i64> {
+    let naive = match time {
+        Some(time) => NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .ok()?
+            .and_time(NaiveTime::parse_from_str(time.trim(), "%H:%M:%S").ok()?),
+        None => NaiveDateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M").ok()?,
+    };
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+/// Read sessions from `content`. Rows that can't be parsed are returned as
+/// messages instead.
+fn read_sessions(
+    content: &str,
+    format: ImportFormat,
+) -> Result<(Vec<ImportedSession>, Vec<String>)> {
+    let mut records = parse_csv(content).into_iter();
+    let (_, header) = records.next().context("The file is empty")?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| h.trim().trim_start_matches('\u{feff}') == name)
+            .with_context(|| format!("Missing column {name:?}"))
+    };
+    let mapping = format.mapping();
+    let goal_col = column(mapping.goal)?;
+    let date_col = column(mapping.start_date)?;
+    let time_col = mapping.start_time.map(column).transpose()?;
+    let duration_col = column(mapping.duration)?;
+
+    let mut sessions = Vec::new();
+    let mut skipped = Vec::new();
+    for (line, record) in records {
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        let field = |col: usize| record.get(col).map(String::as_str).unwrap_or_default();
+        let goal = field(goal_col).trim();
+        let start = parse_start(field(date_col), time_col.map(field));
+        let duration = parse_session_duration(field(duration_col));
+        match (goal.is_empty(), start, duration) {
+            (false, Some(start), Some(duration)) => sessions.push(ImportedSession {
+                line,
+                goal: goal.to_string(),
+                start,
+                duration_secs: duration.min(u32::MAX as u64) as u32,
+            }),
+            _ => skipped.push(format!(
+                "line {line}: couldn't read the goal, start or duration"
+            )),
+        }
+    }
+    Ok((sessions, skipped))
+}
+
+/// Import sessions from a CSV export into `archive`. Sessions are matched to
+/// goals by name, ignoring case; with `create_goals`, unknown names become
+/// new goals, otherwise their rows are reported and skipped.
+pub fn import(archive: &Path, file: &Path, format: ImportFormat, create_goals: bool) -> Result<()> {
+    let content =
+        fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+    let (sessions, mut unmatched) = read_sessions(&content, format)?;
+    let archive = archive.to_string_lossy().to_string();
+
+    let rewards: Vec<u64> =
+        successlib::search_goals(archive.clone(), String::new(), Some(true), None, Some(true))
+            .unwrap_or_default()
+            .iter()
+            .map(|g| g.id)
+            .collect();
+    let mut goals: HashMap<String, (u64, String)> = successlib::list_goals(archive.clone(), None)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|g| (g.name.to_lowercase(), (g.id, g.name)))
+        .collect();
+
+    let mut imported = 0;
+    for session in sessions {
+        let key = session.goal.to_lowercase();
+        if !goals.contains_key(&key) {
+            if !create_goals {
+                unmatched.push(format!(
+                    "line {}: no goal named {:?}",
+                    session.line, session.goal
+                ));
+                continue;
+            }
+            let goal =
+                successlib::add_goal(archive.clone(), session.goal.clone(), false, vec![], None)
+                    .map_err(|e| anyhow::anyhow!("Failed to add goal {:?}: {e:?}", session.goal))?;
+            goals.insert(key.clone(), (goal.id, goal.name));
+        }
+        let (goal_id, name) = &goals[&key];
+        let is_reward = rewards.contains(goal_id);
+        if let Err(e) = successlib::add_session(
+            archive.clone(),
+            *goal_id,
+            name.clone(),
+            session.start,
+            session.duration_secs,
+            is_reward,
+            None,
+        ) {
+            unmatched.push(format!(
+                "line {}: failed to add session: {e:?}",
+                session.line
+            ));
+            continue;
+        }
+        imported += 1;
+    }
+
+    println!("Imported {imported} session(s).");
+    if !unmatched.is_empty() {
+        println!("Skipped {} row(s):", unmatched.len());
+        for message in &unmatched {
+            println!("  {message}");
+        }
+        if !create_goals && unmatched.iter().any(|m| m.contains("no goal named")) {
+            println!("Pass --create-goals to create goals for unknown names.");
+        }
+    }
+    if imported == 0 && !unmatched.is_empty() {
+        bail!("Nothing was imported");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(records: &[(usize, Vec<String>)]) -> Vec<Vec<&str>> {
+        records
+            .iter()
+            .map(|(_, record)| record.iter().map(String::as_str).collect())
+            .collect()
+    }
+
+    #[test]
+    fn csv_handles_quoted_commas_newlines_and_quotes() {
+        let records = parse_csv("a,b\r\n\"x, y\",\"say \"\"hi\"\"\"\n\"two\nlines\",z\n");
+        assert_eq!(
+            fields(&records),
+            vec![
+                vec!["a", "b"],
+                vec!["x, y", "say \"hi\""],
+                vec!["two\nlines", "z"],
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_records_start_on_their_physical_line() {
+        let records = parse_csv("h\n\"one\ntwo\nthree\"\nlast");
+        let lines: Vec<usize> = records.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn toggl_export_with_bom_and_clock_durations() {
+        let content = "\u{feff}Description,Start date,Start time,Duration\n\
+                       \"Write, edit\",2024-05-01,09:00:00,01:30:15\n\
+                       Read,not a date,10:00:00,00:10:00\n";
+        let (sessions, skipped) = read_sessions(content, ImportFormat::Toggl).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].goal, "Write, edit");
+        assert_eq!(sessions[0].duration_secs, 5415);
+        assert_eq!(sessions[0].line, 2);
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("line 3:"));
+    }
+
+    #[test]
+    fn session_durations_accept_clock_and_short_forms() {
+        assert_eq!(parse_session_duration("01:02:03"), Some(3723));
+        assert_eq!(parse_session_duration("1h 30m"), Some(5400));
+        assert_eq!(parse_session_duration("1:xx:00"), None);
+    }
+}
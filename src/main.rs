@@ -1,3 +1,7 @@
+mod commands;
+mod import;
+mod timer_file;
+
 #[cfg(unix)]
 use libc::{kill, setsid, SIGTERM};
 use std::fs;
@@ -6,10 +10,12 @@ use std::io::{self, Write};
 use std::os::unix::process::CommandExt;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::time::Duration;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use anyhow::{bail, Context, Result};
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
 use crossterm::cursor::SetCursorStyle;
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
 use crossterm::execute;
@@ -19,8 +25,9 @@ use ratatui::Terminal;
 use serde::{Deserialize, Serialize};
 
 use success_core::app::AppState;
+use success_core::config::Config;
 use success_core::key_event::{AppKeyCode, AppKeyEvent};
-use success_core::types::Mode;
+use success_core::types::{FocusedBlock, Mode};
 use success_core::ui;
 
 #[cfg(target_os = "macos")]
@@ -76,16 +83,23 @@ fn commands_for_goal(state: &AppState, goal_id: u64) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// Delayed commands sleep in their own shell, so nothing here blocks the UI
+/// and a delayed app is still closed with the others.
 #[cfg(unix)]
 fn spawn_commands(commands: &[String]) -> Vec<SpawnedCommand> {
-    commands
+    commands::plan_commands(commands)
         .iter()
-        .map(|cmd| {
+        .map(|(delay, cmd)| {
+            let script = if *delay == 0 {
+                format!("exec {cmd}")
+            } else {
+                format!("sleep {delay}; exec {cmd}")
+            };
             let child = {
                 let mut command = Command::new("sh");
                 command
                     .arg("-c")
-                    .arg(format!("exec {cmd}"))
+                    .arg(script)
                     .stdin(Stdio::null())
                     .stdout(Stdio::null())
                     .stderr(Stdio::null());
@@ -121,21 +135,31 @@ fn spawn_commands(commands: &[String]) -> Vec<SpawnedCommand> {
 
 #[cfg(not(unix))]
 fn spawn_commands(commands: &[String]) -> Vec<SpawnedCommand> {
-    commands
+    commands::plan_commands(commands)
         .iter()
-        .map(|cmd| {
+        .map(|(delay, cmd)| {
             let child = if cfg!(target_os = "windows") {
+                let script = if *delay == 0 {
+                    cmd.clone()
+                } else {
+                    format!("timeout /t {delay} /nobreak >nul & {cmd}")
+                };
                 Command::new("cmd")
                     .arg("/C")
-                    .arg(cmd)
+                    .arg(script)
                     .stdin(Stdio::null())
                     .stdout(Stdio::null())
                     .stderr(Stdio::null())
                     .spawn()
             } else {
+                let script = if *delay == 0 {
+                    format!("exec {cmd}")
+                } else {
+                    format!("sleep {delay}; exec {cmd}")
+                };
                 Command::new("sh")
                     .arg("-c")
-                    .arg(format!("exec {cmd}"))
+                    .arg(script)
                     .stdin(Stdio::null())
                     .stdout(Stdio::null())
                     .stderr(Stdio::null())
@@ -160,25 +184,32 @@ fn spawn_commands(commands: &[String]) -> Vec<SpawnedCommand> {
         .collect()
 }
 
+/// Send SIGTERM to every spawned process group at once, then SIGKILL the
+/// survivors after a single `grace` period on a background thread so the UI
+/// isn't blocked. Join the returned handle to wait for the cleanup.
 #[cfg(unix)]
-fn kill_spawned(spawned: &mut [SpawnedCommand]) {
-    for sc in spawned.iter_mut() {
-        if let Some(child) = sc.child.as_mut() {
-            let pid = child.id();
-            unsafe {
-                if sc.pgid != 0 {
-                    kill(-sc.pgid, SIGTERM);
-                } else {
-                    kill(-(pid as i32), SIGTERM);
-                }
-            }
-            std::thread::sleep(Duration::from_millis(150));
+fn kill_spawned(spawned: &mut Vec<SpawnedCommand>, grace: Duration) -> JoinHandle<()> {
+    let mut targets: Vec<SpawnedCommand> =
+        spawned.drain(..).filter(|sc| sc.child.is_some()).collect();
+    for sc in &targets {
+        let pgid = spawned_pgid(sc);
+        unsafe {
+            kill(-pgid, SIGTERM);
+        }
+    }
+
+    std::thread::spawn(move || {
+        if targets.is_empty() {
+            return;
+        }
+        std::thread::sleep(grace);
+        for sc in targets.iter_mut() {
+            let pgid = spawned_pgid(sc);
+            let Some(child) = sc.child.as_mut() else {
+                continue;
+            };
             unsafe {
-                if sc.pgid != 0 {
-                    kill(-sc.pgid, libc::SIGKILL);
-                } else {
-                    kill(-(pid as i32), libc::SIGKILL);
-                }
+                kill(-pgid, libc::SIGKILL);
             }
             if let Err(err) = child.kill() {
                 if err.kind() != io::ErrorKind::InvalidInput {
@@ -194,20 +225,98 @@ fn kill_spawned(spawned: &mut [SpawnedCommand]) {
                 .stderr(Stdio::null())
                 .status();
         }
+    })
+}
+
+#[cfg(unix)]
+fn spawned_pgid(sc: &SpawnedCommand) -> i32 {
+    if sc.pgid != 0 {
+        sc.pgid
+    } else {
+        sc.child.as_ref().map(|c| c.id() as i32).unwrap_or(0)
     }
 }
 
 #[cfg(not(unix))]
-fn kill_spawned(spawned: &mut [SpawnedCommand]) {
-    for sc in spawned.iter_mut() {
-        if let Some(mut child) = sc.child.take() {
-            if let Err(err) = child.kill() {
-                if err.kind() != io::ErrorKind::InvalidInput {
-                    eprintln!("Failed to kill '{}': {err}", sc.command);
+fn kill_spawned(spawned: &mut Vec<SpawnedCommand>, _grace: Duration) -> JoinHandle<()> {
+    let mut targets: Vec<SpawnedCommand> = spawned.drain(..).collect();
+    std::thread::spawn(move || {
+        for sc in targets.iter_mut() {
+            if let Some(mut child) = sc.child.take() {
+                if let Err(err) = child.kill() {
+                    if err.kind() != io::ErrorKind::InvalidInput {
+                        eprintln!("Failed to kill '{}': {err}", sc.command);
+                    }
                 }
+                let _ = child.wait();
             }
-            let _ = child.wait();
         }
+    })
+}
+
+// ── CLI-specific: do not disturb ─────────────────────────────────────────
+
+#[cfg(target_os = "macos")]
+const DND_ON_COMMAND: Option<&str> =
+    Some("defaults -currentHost write com.apple.notificationcenterui doNotDisturb -boolean true && killall NotificationCenter");
+#[cfg(target_os = "macos")]
+const DND_OFF_COMMAND: Option<&str> =
+    Some("defaults -currentHost write com.apple.notificationcenterui doNotDisturb -boolean false && killall NotificationCenter");
+#[cfg(all(unix, not(target_os = "macos")))]
+const DND_ON_COMMAND: Option<&str> =
+    Some("gsettings set org.gnome.desktop.notifications show-banners false");
+#[cfg(all(unix, not(target_os = "macos")))]
+const DND_OFF_COMMAND: Option<&str> =
+    Some("gsettings set org.gnome.desktop.notifications show-banners true");
+#[cfg(not(unix))]
+const DND_ON_COMMAND: Option<&str> = None;
+#[cfg(not(unix))]
+const DND_OFF_COMMAND: Option<&str> = None;
+
+/// Run the configured (or platform default) do-not-disturb command.
+fn set_do_not_disturb(cfg: &CliConfig, enabled: bool) -> Result<()> {
+    let command = if enabled {
+        cfg.do_not_disturb_on.as_deref().or(DND_ON_COMMAND)
+    } else {
+        cfg.do_not_disturb_off.as_deref().or(DND_OFF_COMMAND)
+    };
+    let Some(command) = command else {
+        bail!("Do not disturb is not supported on this platform");
+    };
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c
+    };
+    let status = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run '{command}'"))?;
+    if !status.success() {
+        bail!("'{command}' exited with status {status}");
+    }
+    Ok(())
+}
+
+/// Keep do-not-disturb on exactly while a goal (not reward) timer runs.
+fn sync_do_not_disturb(cli: &mut CliState) {
+    if !cli.config.do_not_disturb {
+        return;
+    }
+    let wanted = cli.app.timer.as_ref().is_some_and(|t| !t.is_reward);
+    if wanted == cli.dnd_active {
+        return;
+    }
+    cli.dnd_active = wanted;
+    if let Err(err) = set_do_not_disturb(&cli.config, wanted) {
+        cli.app.status = Some(format!("Do not disturb: {err}"));
     }
 }
 
@@ -227,13 +336,52 @@ fn open_archive_in_file_manager(archive: &Path) -> Result<()> {
     Ok(())
 }
 
-fn open_notes_in_external_editor(state: &mut AppState, archive: &Path) -> Result<()> {
-    let goal_id = success_core::utils::selected_goal_id(state)
-        .context("No goal selected for note editing")?;
+/// Show where the selected goal's files are, for scripts working on the
+/// archive. success-lib doesn't expose where it keeps sessions, so only
+/// the archive folder is shown for those.
+fn show_goal_paths(state: &mut AppState) {
+    let Some(goal_id) = success_core::utils::selected_goal_id(state) else {
+        state.status = Some("No goal selected".to_string());
+        return;
+    };
+    let goal_name = state
+        .goals
+        .iter()
+        .find(|g| g.id == goal_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_default();
+    let archive = Path::new(&state.archive_path);
+    let paths = [
+        ("Archive (sessions)", archive.to_path_buf()),
+        (
+            "Notes",
+            success_core::notes::note_path(state, goal_id, state.current_day),
+        ),
+        (
+            "Goal settings",
+            success_core::goal_meta::meta_path(&state.archive_path),
+        ),
+        (
+            "Session settings",
+            success_core::session_meta::meta_path(&state.archive_path),
+        ),
+    ];
+    state.mode = Mode::GoalPaths {
+        goal_name: format!("{goal_name} (id {goal_id})"),
+        paths: paths
+            .into_iter()
+            .map(|(label, path)| (label.to_string(), path.display().to_string()))
+            .collect(),
+    };
+}
+
+fn open_notes_in_external_editor(state: &mut AppState) -> Result<()> {
+    let (goal_id, day) =
+        success_core::notes::notes_target(state).context("No goal selected for note editing")?;
     success_core::notes::refresh_notes_for_selection(state);
     success_core::notes::save_notes_for_selection(state);
 
-    let note_path = archive.join("notes").join(format!("goal_{goal_id}.md"));
+    let note_path = success_core::notes::note_path(state, goal_id, day);
 
     let editor_value = std::env::var("EDITOR").unwrap_or_else(|_| DEFAULT_EDITOR.to_string());
     let mut editor_parts = parse_editor_command(editor_value.trim());
@@ -340,8 +488,90 @@ fn parse_editor_command(input: &str) -> Vec<String> {
 // ── Config ───────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 struct CliConfig {
     archive: Option<PathBuf>,
+    /// Turn on the OS do-not-disturb mode while a goal timer runs.
+    do_not_disturb: bool,
+    /// Overrides for the platform's do-not-disturb on/off shell commands.
+    do_not_disturb_on: Option<String>,
+    do_not_disturb_off: Option<String>,
+    /// Milliseconds between SIGTERM and SIGKILL when closing spawned apps.
+    kill_grace_ms: u64,
+    /// Open the new session's notes in `$EDITOR` as soon as a timer starts.
+    external_notes_on_start: bool,
+    /// Ring the terminal bell each second of a timer's last this many seconds.
+    countdown_ticks: Option<u32>,
+    /// Minutes without a key press after which a running timer counts the
+    /// time as idle and leaves it out of the session.
+    idle_after_mins: Option<u64>,
+    /// Passive dashboard: always show today, rolling over at midnight, and
+    /// ignore day navigation.
+    kiosk: bool,
+    /// Options shared with the core, stored alongside the CLI-only ones.
+    #[serde(flatten)]
+    core: Config,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            archive: None,
+            do_not_disturb: false,
+            do_not_disturb_on: None,
+            do_not_disturb_off: None,
+            kill_grace_ms: 150,
+            external_notes_on_start: false,
+            countdown_ticks: None,
+            idle_after_mins: None,
+            kiosk: false,
+            core: Config::default(),
+        }
+    }
+}
+
+fn load_config() -> CliConfig {
+    config_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<CliConfig>(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Like [`load_config`], but a `config.json` that fails to parse is copied to
+/// `config.json.bak` before defaults are used, keeping its `archive` if that
+/// key can still be read. Returns the warning to show.
+fn load_config_checked() -> (CliConfig, Option<String>) {
+    let Some((path, content)) = config_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(&path).ok().map(|c| (path, c)))
+    else {
+        return (CliConfig::default(), None);
+    };
+    let err = match serde_json::from_str::<CliConfig>(&content) {
+        Ok(cfg) => return (cfg, None),
+        Err(err) => err,
+    };
+    let backup = path.with_extension("json.bak");
+    let warning = match fs::copy(&path, &backup) {
+        Ok(_) => format!(
+            "Could not parse {}: {err}; saved a copy to {} and using defaults",
+            path.display(),
+            backup.display()
+        ),
+        Err(e) => format!(
+            "Could not parse {}: {err}; using defaults (backup failed: {e})",
+            path.display()
+        ),
+    };
+    let archive = serde_json::from_str::<serde_json::Value>(&content)
+        .ok()
+        .and_then(|v| v.get("archive")?.as_str().map(PathBuf::from));
+    let cfg = CliConfig {
+        archive,
+        ..CliConfig::default()
+    };
+    (cfg, Some(warning))
 }
 
 fn persist_config(archive: &Path) -> Result<()> {
@@ -349,38 +579,244 @@ fn persist_config(archive: &Path) -> Result<()> {
     if let Some(dir) = path.parent() {
         fs::create_dir_all(dir)?;
     }
-    let cfg = CliConfig {
-        archive: Some(archive.to_path_buf()),
-    };
+    let mut cfg = load_config();
+    cfg.archive = Some(archive.to_path_buf());
     let content = serde_json::to_string_pretty(&cfg)?;
     fs::write(&path, content)?;
     Ok(())
 }
 
-fn resolve_archive_interactive(preferred: Option<PathBuf>) -> Result<PathBuf> {
+/// CLI-only options as a JSON object for the settings screen: all keys but
+/// `archive` and the ones shared with the core.
+fn cli_settings(cfg: &CliConfig) -> serde_json::Value {
+    let mut value = serde_json::to_value(cfg).unwrap_or_default();
+    let core = serde_json::to_value(&cfg.core).unwrap_or_default();
+    if let (Some(map), Some(core)) = (value.as_object_mut(), core.as_object()) {
+        map.remove("archive");
+        map.retain(|key, _| !core.contains_key(key));
+    }
+    value
+}
+
+/// `cfg` with the CLI-only options taken from `settings`.
+fn with_cli_settings(cfg: &CliConfig, settings: &serde_json::Value) -> Result<CliConfig, String> {
+    let mut value = serde_json::to_value(cfg).map_err(|e| e.to_string())?;
+    if let (Some(map), Some(settings)) = (value.as_object_mut(), settings.as_object()) {
+        map.extend(settings.clone());
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+fn check_cli_settings(settings: &serde_json::Value) -> Result<(), String> {
+    with_cli_settings(&CliConfig::default(), settings).map(|_| ())
+}
+
+/// Write options changed on the settings screen back to `config.json`,
+/// keeping its `archive`. A file that can't be parsed is left alone rather
+/// than replaced with defaults.
+fn persist_settings(settings: &CliConfig) -> Result<()> {
+    let path = config_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str::<CliConfig>(&content)
+            .with_context(|| format!("{} can't be parsed; not overwriting it", path.display()))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => CliConfig::default(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let cfg = CliConfig {
+        archive: file.archive,
+        ..settings.clone()
+    };
+    fs::write(&path, serde_json::to_string_pretty(&cfg)?)?;
+    Ok(())
+}
+
+/// Environment variable naming the archive, checked after `--archive` and
+/// before the config file.
+const ARCHIVE_ENV: &str = "SUCCESS_ARCHIVE";
+
+/// The archive given by `--archive` or, failing that, `SUCCESS_ARCHIVE`.
+fn archive_override(flag: Option<PathBuf>) -> Option<PathBuf> {
+    flag.or_else(|| {
+        std::env::var_os(ARCHIVE_ENV)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+fn resolve_archive_interactive(preferred: Option<PathBuf>, cfg: &CliConfig) -> Result<PathBuf> {
     if let Some(path) = preferred {
         return Ok(path);
     }
-    let cfg_path = config_path()?;
-    if cfg_path.exists() {
-        if let Ok(content) = fs::read_to_string(&cfg_path) {
-            if let Ok(cfg) = serde_json::from_str::<CliConfig>(&content) {
-                if let Some(p) = cfg.archive {
-                    return Ok(p);
-                }
-            }
+    if let Some(p) = &cfg.archive {
+        return Ok(p.clone());
+    }
+    let path = prompt_archive_path(cfg.core.accent())?;
+    fs::create_dir_all(&path)
+        .with_context(|| format!("Failed to create archive folder {}", path.display()))?;
+    Ok(path)
+}
+
+/// Expand a leading `~` to `$HOME`.
+fn expand_home(input: &str) -> PathBuf {
+    match (input.strip_prefix('~'), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) if rest.is_empty() || rest.starts_with('/') => {
+            PathBuf::from(format!("{home}{rest}"))
         }
+        _ => PathBuf::from(input),
     }
-    println!("Archive folder not set. Enter a path to use (will be created if missing):");
-    let mut line = String::new();
-    io::stdin().read_line(&mut line)?;
-    let trimmed = line.trim();
-    if trimmed.is_empty() {
-        bail!("Archive folder not provided");
+}
+
+/// Complete the last component of `input` to the folders it could name.
+/// Returns the completed input (extended to the matches' common prefix, with
+/// a trailing `/` when only one matches) and the matching folder names.
+fn complete_archive_path(input: &str) -> (String, Vec<String>) {
+    if input == "~" {
+        return ("~/".to_string(), Vec::new());
     }
-    let path = PathBuf::from(trimmed);
-    fs::create_dir_all(&path)?;
-    Ok(path)
+    let (dir, prefix) = match input.rfind('/') {
+        Some(idx) => input.split_at(idx + 1),
+        None => ("", input),
+    };
+    let read_from = if dir.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir)
+    };
+    let mut matches: Vec<String> = fs::read_dir(&read_from)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with(prefix))
+                .filter(|name| prefix.starts_with('.') || !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+    matches.sort();
+    let completed = match matches.as_slice() {
+        [] => input.to_string(),
+        [only] => format!("{dir}{only}/"),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.as_str(), |common, name| {
+                let len = common
+                    .char_indices()
+                    .zip(name.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .last()
+                    .map_or(0, |((idx, a), _)| idx + a.len_utf8());
+                &common[..len]
+            });
+            format!("{dir}{common}")
+        }
+    };
+    (completed, matches)
+}
+
+/// What entering `input` as the archive would do, and whether it is allowed.
+fn archive_path_hint(input: &str) -> (String, bool) {
+    if input.is_empty() {
+        return ("Type a folder path".to_string(), false);
+    }
+    let path = expand_home(input);
+    if path.is_dir() {
+        (
+            "Existing folder; its goals and sessions will be used".to_string(),
+            true,
+        )
+    } else if path.exists() {
+        ("Not a folder".to_string(), false)
+    } else {
+        ("Folder will be created".to_string(), true)
+    }
+}
+
+/// Ask for the archive folder on a small TUI screen before the app starts.
+fn prompt_archive_path(accent: ratatui::style::Color) -> Result<PathBuf> {
+    use ratatui::layout::{Constraint, Direction, Layout};
+    use ratatui::style::Style;
+    use ratatui::widgets::{Block, Borders, Paragraph};
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        terminal::EnterAlternateScreen,
+        SetCursorStyle::SteadyBlock
+    )?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let mut input = success_core::types::single_line_textarea_from_string(String::new());
+    // Folders listed after a Tab that matched several.
+    let mut candidates: Option<String> = None;
+    // Run the loop in a closure so the terminal is restored on errors too.
+    let mut run = || -> Result<PathBuf> {
+        loop {
+            let value = success_core::types::single_line_textarea_value(&input);
+            let (hint, valid) = archive_path_hint(value.trim());
+            let hint = candidates.clone().unwrap_or(hint);
+            terminal.draw(|f| {
+                let area = success_core::utils::centered_rect_fixed_height(70, 5, f.area());
+                let block = Block::default()
+                    .borders(Borders::ALL)
+                    .title("Archive folder (stores your goals and sessions)")
+                    .border_style(Style::default().fg(accent));
+                let inner = block.inner(area);
+                f.render_widget(block, area);
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Length(1),
+                        Constraint::Length(1),
+                        Constraint::Length(1),
+                    ])
+                    .split(inner);
+                f.render_widget(&input, rows[0]);
+                f.render_widget(Paragraph::new(hint.as_str()), rows[1]);
+                f.render_widget(
+                    Paragraph::new("Enter: use folder • Tab: complete • Esc: quit")
+                        .style(Style::default().fg(success_core::style::GRAY_DIM)),
+                    rows[2],
+                );
+            })?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            let app_key = convert_key(key);
+            if app_key.is_ctrl_c() || app_key.code == AppKeyCode::Esc {
+                return Err(anyhow::anyhow!("Archive folder not provided"));
+            }
+            if app_key.code == AppKeyCode::Enter {
+                if valid {
+                    return Ok(expand_home(value.trim()));
+                }
+                continue;
+            }
+            candidates = None;
+            if app_key.code == AppKeyCode::Tab {
+                let (completed, matches) = complete_archive_path(&value);
+                if matches.len() > 1 {
+                    candidates = Some(matches.join("  "));
+                }
+                input = success_core::types::single_line_textarea_from_string(completed);
+                continue;
+            }
+            success_core::types::handle_single_line_textarea_key(&mut input, &app_key);
+        }
+    };
+    let result = run();
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        terminal::LeaveAlternateScreen,
+        SetCursorStyle::DefaultUserShape
+    )?;
+    result
 }
 
 fn config_path() -> Result<PathBuf> {
@@ -388,6 +824,68 @@ fn config_path() -> Result<PathBuf> {
     Ok(Path::new(&home).join(".config/success-cli/config.json"))
 }
 
+// ── UI state ─────────────────────────────────────────────────────────────
+
+/// Lightweight UI state remembered across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UiState {
+    archive: PathBuf,
+    day: NaiveDate,
+    goal_id: Option<u64>,
+    focused_block: FocusedBlock,
+}
+
+fn ui_state_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME").context("HOME not set; please set HOME")?;
+    Ok(Path::new(&home).join(".config/success-cli/state.json"))
+}
+
+fn persist_ui_state(cli: &CliState) -> Result<()> {
+    let path = ui_state_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let ui_state = UiState {
+        archive: cli.archive.clone(),
+        day: cli.app.current_day,
+        goal_id: success_core::utils::selected_goal_id(&cli.app),
+        focused_block: cli.app.focused_block,
+    };
+    fs::write(&path, serde_json::to_string_pretty(&ui_state)?)?;
+    Ok(())
+}
+
+fn restore_ui_state(cli: &mut CliState) {
+    let Ok(path) = ui_state_path() else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return;
+    };
+    let Ok(ui_state) = serde_json::from_str::<UiState>(&content) else {
+        return;
+    };
+    // Goal ids are only meaningful within the archive they were saved for.
+    if ui_state.archive != cli.archive {
+        return;
+    }
+    cli.app
+        .restore_view(ui_state.day, ui_state.goal_id, ui_state.focused_block);
+}
+
+/// Whether the locale (`LC_ALL`, then `LC_CTYPE`, then `LANG`) asks for UTF-8.
+/// An unset locale is assumed to support it, as most terminals do.
+fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .is_none_or(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
 // ── Main ─────────────────────────────────────────────────────────────────
 
 #[derive(Parser, Debug)]
@@ -397,6 +895,73 @@ struct Args {
     /// Custom archive path (useful for testing)
     #[arg(short, long)]
     archive: Option<PathBuf>,
+
+    /// Use a fresh temporary archive that is deleted on exit
+    #[arg(long, conflicts_with = "archive")]
+    dry_run: bool,
+
+    /// Draw the timer gauge and sparkline with ASCII (the default when the
+    /// locale isn't UTF-8)
+    #[arg(long)]
+    ascii: bool,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Print the running timer as one line of JSON (for status bars)
+    Status {
+        /// Custom output, e.g. "{label} {mmss}"; prints an empty line when idle
+        #[arg(long)]
+        format: Option<String>,
+    },
+    /// Check goals for setup problems, such as commands that kill each other
+    Doctor,
+    /// Print goal names and ids, tab-separated, one per line (for shell completion)
+    Goals {
+        /// Only list rewards
+        #[arg(long, conflicts_with = "goals_only")]
+        rewards_only: bool,
+        /// Only list goals that aren't rewards
+        #[arg(long)]
+        goals_only: bool,
+    },
+    /// Import sessions from another time tracker's CSV export
+    Import {
+        /// CSV file to read
+        file: PathBuf,
+        #[arg(long, value_enum, default_value = "csv")]
+        format: import::ImportFormat,
+        /// Create goals for names that don't match an existing goal
+        #[arg(long)]
+        create_goals: bool,
+    },
+    /// Print the time recorded between two days, e.g. for last month
+    Total {
+        /// First day, e.g. "2024-05-01", "2024-05" or "-30"
+        #[arg(long)]
+        from: String,
+        /// Last day, included (default today)
+        #[arg(long, default_value = "today")]
+        to: String,
+        /// Only count the goal or reward with this name
+        #[arg(long)]
+        goal: Option<String>,
+        /// Print one line of JSON instead
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a Markdown summary of a day's sessions to summaries/YYYY-MM-DD.md
+    Export {
+        /// Summarise a single day (the only export for now)
+        #[arg(long, required = true)]
+        daily: bool,
+        /// Day to summarise, e.g. "2024-05-01" or "yesterday" (default today)
+        #[arg(long)]
+        day: Option<String>,
+    },
 }
 
 /// CLI-extended state: wraps core state + CLI-only fields
@@ -404,24 +969,141 @@ struct CliState {
     app: AppState,
     archive: PathBuf,
     spawned: Vec<SpawnedCommand>,
+    /// Goal whose timer started the apps in `spawned`.
+    spawned_goal: Option<u64>,
     needs_full_redraw: bool,
+    /// Start time of the timer last written to `timer.json`.
+    persisted_timer: Option<chrono::DateTime<chrono::Utc>>,
+    config: CliConfig,
+    dnd_active: bool,
+    /// Archive is a throwaway temp dir; nothing is persisted.
+    dry_run: bool,
+    /// Remaining seconds at the last countdown bell.
+    last_countdown_bell: Option<u64>,
+    /// Time of the last key press, for idle detection.
+    last_input: Instant,
+    /// Idle seconds of the current gap already added to the timer.
+    counted_idle: u64,
+}
+
+impl CliState {
+    fn kill_grace(&self) -> Duration {
+        Duration::from_millis(self.config.kill_grace_ms)
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let archive = resolve_archive_interactive(args.archive.clone())?;
+    let (cfg, config_warning) = load_config_checked();
+    if let Some(warning) = &config_warning {
+        eprintln!("Warning: {warning}");
+    }
+
+    let archive_override = archive_override(args.archive.clone());
+
+    if let Some(command) = &args.command {
+        let archive = archive_override
+            .clone()
+            .or_else(|| cfg.archive.clone())
+            .with_context(|| {
+                format!("Archive folder not set; run success-cli once, pass --archive or set {ARCHIVE_ENV}")
+            })?;
+        return match command {
+            Commands::Status { format } => commands::status(&archive, format.as_deref()),
+            Commands::Doctor => commands::doctor(&archive),
+            Commands::Goals {
+                rewards_only,
+                goals_only,
+            } => {
+                let is_reward = match (rewards_only, goals_only) {
+                    (true, _) => Some(true),
+                    (_, true) => Some(false),
+                    _ => None,
+                };
+                commands::goals(&archive, is_reward)
+            }
+            Commands::Import {
+                file,
+                format,
+                create_goals,
+            } => import::import(&archive, file, *format, *create_goals),
+            Commands::Total {
+                from,
+                to,
+                goal,
+                json,
+            } => commands::total(&archive, from, to, goal.as_deref(), *json),
+            Commands::Export { daily: _, day } => {
+                commands::export_daily(&archive, day.as_deref(), &cfg.core)
+            }
+        };
+    }
 
-    let app = AppState::new(archive.to_string_lossy().to_string());
+    let archive = if args.dry_run {
+        let dir = dry_run_archive()?;
+        println!(
+            "Dry run: using temporary archive {}; changes won't persist.",
+            dir.display()
+        );
+        dir
+    } else {
+        resolve_archive_interactive(archive_override.clone(), &cfg)?
+    };
+
+    let mut app = AppState::new(archive.to_string_lossy().to_string());
+    app.config = cfg.core.clone();
+    app.kiosk = cfg.kiosk;
+    app.cli_settings = cli_settings(&cfg);
+    app.check_cli_settings = Some(check_cli_settings);
+    app.ascii = args.ascii || !locale_is_utf8();
+    app.selected = success_core::utils::default_selection(&app);
+    success_core::notes::migrate_to_per_day(&app);
+    success_core::notes::refresh_notes_for_selection(&mut app);
+    success_core::notes::refresh_note_snippets(&mut app);
+    let overlaps = commands::find_command_overlaps(&app.goals);
+    if !overlaps.is_empty() {
+        app.status = Some(format!(
+            "{} goal command overlap(s) may close each other's apps; run `success-cli doctor`",
+            overlaps.len()
+        ));
+    }
+    if let Some(accent) = &app.config.accent_color {
+        if success_core::style::parse_hex(accent).is_none() {
+            app.status = Some(format!(
+                "Invalid accent_color {accent:?}; expected \"#RRGGBB\""
+            ));
+        }
+    }
+    if let Some(warning) = config_warning {
+        app.status = Some(warning);
+    }
 
     let mut cli = CliState {
         app,
         archive: archive.clone(),
         spawned: Vec::new(),
+        spawned_goal: None,
         needs_full_redraw: false,
+        persisted_timer: None,
+        config: cfg,
+        dnd_active: false,
+        dry_run: args.dry_run,
+        last_countdown_bell: None,
+        last_input: Instant::now(),
+        counted_idle: 0,
     };
 
-    if args.archive.is_none() {
-        persist_config(&archive).ok();
+    if !cli.dry_run {
+        // An archive from the flag or environment is a one-off; keep the
+        // configured one.
+        if archive_override.is_none() {
+            persist_config(&archive).ok();
+        }
+        // A kiosk always shows today, so the last viewed day isn't restored.
+        if !cli.config.kiosk {
+            restore_ui_state(&mut cli);
+        }
+        cli.app.offer_interrupted_session();
     }
 
     enable_raw_mode()?;
@@ -436,6 +1118,9 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     let res = run_app(&mut terminal, &mut cli);
+    if !cli.dry_run {
+        persist_ui_state(&cli).ok();
+    }
 
     disable_raw_mode()?;
     execute!(
@@ -446,12 +1131,28 @@ fn main() -> Result<()> {
     )?;
     terminal.show_cursor()?;
 
+    if cli.dry_run {
+        fs::remove_dir_all(&cli.archive).ok();
+        println!("Dry run finished; temporary archive removed.");
+    }
+
     if let Err(err) = res {
         eprintln!("Error: {err}");
     }
     Ok(())
 }
 
+fn dry_run_archive() -> Result<PathBuf> {
+    let stamp = chrono::Utc::now().timestamp_millis();
+    let dir = std::env::temp_dir().join(format!(
+        "success-cli-dry-run-{}-{stamp}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create temporary archive {}", dir.display()))?;
+    Ok(dir)
+}
+
 fn run_app<B: ratatui::backend::Backend<Error: Send + Sync + 'static> + std::io::Write>(
     terminal: &mut Terminal<B>,
     cli: &mut CliState,
@@ -461,29 +1162,117 @@ fn run_app<B: ratatui::backend::Backend<Error: Send + Sync + 'static> + std::io:
             terminal.clear()?;
             cli.needs_full_redraw = false;
         }
+        count_idle_time(cli);
         cli.app.tick();
+        // A timer can finish without a key press, e.g. when the computer
+        // wakes up after its end time, so don't wait for input to kill apps.
+        kill_spawned_if_finished(cli);
+        sync_timer_file(cli);
+        sync_do_not_disturb(cli);
+        ring_countdown_bell(cli);
 
-        let header = format!("Archive: {} (open with 'o')", cli.archive.display());
+        let header = if cli.dry_run {
+            format!(
+                "Dry run — changes won't persist: {} (open with 'o')",
+                cli.archive.display()
+            )
+        } else {
+            format!("Archive: {} (open with 'o')", cli.archive.display())
+        };
         terminal.draw(|f| ui::ui(f, &cli.app, &header))?;
         execute!(terminal.backend_mut(), get_cursor_style(&cli.app.mode))?;
 
-        if event::poll(Duration::from_millis(200))? {
+        if event::poll(poll_timeout(&cli.app))? {
             match event::read()? {
                 Event::Key(key) => {
                     // Handle CLI-specific keys first
                     if handle_cli_key(cli, key)? {
                         break;
                     }
+                    // After handling, so time in an external editor isn't idle.
+                    cli.last_input = Instant::now();
+                    cli.counted_idle = 0;
                 }
                 Event::Resize(_, _) => {}
                 _ => {}
             }
         }
     }
-    kill_spawned(&mut cli.spawned);
+    let _ = kill_spawned(&mut cli.spawned, cli.kill_grace()).join();
+    timer_file::clear(&cli.archive).ok();
+    if cli.dnd_active {
+        set_do_not_disturb(&cli.config, false).ok();
+    }
     Ok(())
 }
 
+/// Longest wait for input before the next tick and redraw.
+const IDLE_POLL: Duration = Duration::from_millis(200);
+
+/// How long to wait for input. While a timer runs, wake just after its next
+/// whole second so the countdown changes on time instead of up to a poll
+/// interval late.
+fn poll_timeout(app: &AppState) -> Duration {
+    let Some(timer) = &app.timer else {
+        return IDLE_POLL;
+    };
+    let elapsed_ms = (chrono::Utc::now() - timer.started_at)
+        .num_milliseconds()
+        .max(0) as u64;
+    let to_next_second = Duration::from_millis(1000 - elapsed_ms % 1000 + 5);
+    to_next_second.min(IDLE_POLL)
+}
+
+/// Add time beyond `idle_after_mins` without a key press to the running
+/// timer's idle time. Runs before the tick so a timer that ends while idle
+/// records it too.
+fn count_idle_time(cli: &mut CliState) {
+    let (Some(mins), Some(timer)) = (cli.config.idle_after_mins, cli.app.timer.as_mut()) else {
+        return;
+    };
+    let idle = cli.last_input.elapsed().as_secs().saturating_sub(mins * 60);
+    timer.idle_secs += idle.saturating_sub(cli.counted_idle);
+    cli.counted_idle = idle;
+}
+
+/// Ring the bell once per second in the timer's final `countdown_ticks`
+/// seconds. BEL moves no cursor, so the alternate screen is left intact.
+fn ring_countdown_bell(cli: &mut CliState) {
+    let remaining = cli.app.timer.as_ref().map(|t| t.remaining);
+    let in_window = cli
+        .config
+        .countdown_ticks
+        .zip(remaining)
+        .is_some_and(|(ticks, remaining)| remaining > 0 && remaining <= ticks as u64);
+    if !in_window {
+        cli.last_countdown_bell = None;
+        return;
+    }
+    if cli.last_countdown_bell != remaining {
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07");
+        let _ = stdout.flush();
+        cli.last_countdown_bell = remaining;
+    }
+}
+
+/// Keep `timer.json` in step with the running timer.
+fn sync_timer_file(cli: &mut CliState) {
+    let current = cli.app.timer.as_ref().map(|t| t.started_at);
+    if current == cli.persisted_timer {
+        return;
+    }
+    match &cli.app.timer {
+        Some(timer) => {
+            timer_file::write(&cli.archive, &timer_file::PersistedTimer::from_timer(timer)).ok();
+        }
+        None => {
+            timer_file::clear(&cli.archive).ok();
+        }
+    }
+    cli.persisted_timer = current;
+}
+
 /// Handle CLI-specific key actions, then delegate to core.
 /// Returns true if the app should quit.
 fn handle_cli_key(cli: &mut CliState, key: crossterm::event::KeyEvent) -> Result<bool> {
@@ -491,8 +1280,8 @@ fn handle_cli_key(cli: &mut CliState, key: crossterm::event::KeyEvent) -> Result
     if matches!(cli.app.mode, Mode::View) {
         match key.code {
             KeyCode::Char('E') => {
-                if success_core::utils::selected_goal_id(&cli.app).is_some() {
-                    open_notes_in_external_editor(&mut cli.app, &cli.archive)?;
+                if success_core::notes::notes_target(&cli.app).is_some() {
+                    open_notes_in_external_editor(&mut cli.app)?;
                     cli.needs_full_redraw = true;
                 }
                 return Ok(false);
@@ -501,40 +1290,99 @@ fn handle_cli_key(cli: &mut CliState, key: crossterm::event::KeyEvent) -> Result
                 let _ = open_archive_in_file_manager(&cli.archive);
                 return Ok(false);
             }
+            KeyCode::Char('P') => {
+                show_goal_paths(&mut cli.app);
+                return Ok(false);
+            }
             _ => {}
         }
     }
 
     // Delegate to core
+    let had_timer = cli.app.timer.is_some();
     let app_key = convert_key(key);
     let quit = cli.app.handle_key(app_key);
 
-    // After core handles a timer start, spawn commands
-    if matches!(cli.app.mode, Mode::Timer) {
+    if cli.app.config_changed {
+        cli.app.config_changed = false;
+        cli.config.core = cli.app.config.clone();
+        if let Ok(cfg) = with_cli_settings(&cli.config, &cli.app.cli_settings) {
+            cli.config = cfg;
+        }
+        cli.app.kiosk = cli.config.kiosk;
+        if !cli.dry_run {
+            if let Err(e) = persist_settings(&cli.config) {
+                cli.app.status = Some(format!("Failed to save settings: {e:#}"));
+            }
+        }
+    }
+
+    // After core handles a timer start, spawn commands. `notes_on_start`
+    // starts the timer in the notes editor.
+    if matches!(cli.app.mode, Mode::Timer | Mode::NotesEdit) {
         if let Some(timer) = &cli.app.timer {
-            if cli.spawned.is_empty() {
+            if cli.spawned.is_empty() && !timer.skip_commands {
                 let cmds = commands_for_goal(&cli.app, timer.goal_id);
                 cli.spawned = spawn_commands(&cmds);
+                cli.spawned_goal = Some(timer.goal_id);
             }
         }
     }
 
-    // When timer finishes and was a reward, kill spawned apps
-    if !matches!(cli.app.mode, Mode::Timer) && !cli.spawned.is_empty() {
-        kill_spawned(&mut cli.spawned);
+    // The timer is wall-clock based, so it keeps counting while the editor
+    // is open.
+    if !had_timer && cli.app.timer.is_some() && cli.config.external_notes_on_start {
+        if let Err(e) = open_notes_in_external_editor(&mut cli.app) {
+            cli.app.status = Some(format!("{e:#}"));
+        }
+        cli.needs_full_redraw = true;
     }
 
+    kill_spawned_if_finished(cli);
+
     Ok(quit)
 }
 
+/// Kill spawned apps once their timer has finished, unless their goal has
+/// `kill_on_end` turned off. Checking the timer rather than the mode keeps
+/// them alive while a popup or notes editing is open.
+fn kill_spawned_if_finished(cli: &mut CliState) {
+    if cli.app.timer.is_some() || cli.spawned.is_empty() {
+        return;
+    }
+    let kill_on_end = cli
+        .spawned_goal
+        .take()
+        .is_none_or(|goal_id| cli.app.goal_meta(goal_id).kill_on_end);
+    if kill_on_end {
+        let grace = cli.kill_grace();
+        kill_spawned(&mut cli.spawned, grace);
+    } else {
+        // Leave the apps running; they are no longer ours to close.
+        cli.spawned.clear();
+    }
+}
+
 fn get_cursor_style(mode: &Mode) -> SetCursorStyle {
     match mode {
         Mode::NotesEdit
         | Mode::AddSession
         | Mode::AddReward
+        | Mode::AddCheckpoint
+        | Mode::AddBackfill
         | Mode::GoalForm
         | Mode::QuantityDoneInput { .. }
-        | Mode::DurationInput { .. } => SetCursorStyle::SteadyBlock,
-        Mode::View | Mode::Timer => SetCursorStyle::SteadyBlock,
+        | Mode::DurationInput { .. }
+        | Mode::Prompt { .. }
+        | Mode::Settings { editing: true, .. }
+        | Mode::RecoverSession { .. } => SetCursorStyle::SteadyBlock,
+        Mode::View
+        | Mode::Timer
+        | Mode::SessionSummary { .. }
+        | Mode::Insights
+        | Mode::GoalHistory { .. }
+        | Mode::GoalPaths { .. }
+        | Mode::Settings { editing: false, .. }
+        | Mode::ConfirmReward { .. } => SetCursorStyle::SteadyBlock,
     }
 }
@@ -0,0 +1,64 @@
+//! Persists the running timer to `timer.json` in the archive so that other
+//! processes (e.g. the `status` subcommand) can observe it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use success_core::types::TimerState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedTimer {
+    pub label: String,
+    pub goal_id: u64,
+    pub is_reward: bool,
+    pub started_at: DateTime<Utc>,
+    pub total: u64,
+}
+
+impl PersistedTimer {
+    pub fn from_timer(timer: &TimerState) -> Self {
+        Self {
+            label: timer.label.clone(),
+            goal_id: timer.goal_id,
+            is_reward: timer.is_reward,
+            started_at: timer.started_at,
+            total: timer.total,
+        }
+    }
+
+    /// Seconds left according to the wall clock, or `None` once elapsed.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<u64> {
+        let elapsed = (now - self.started_at).num_seconds().max(0) as u64;
+        if elapsed >= self.total {
+            None
+        } else {
+            Some(self.total - elapsed)
+        }
+    }
+}
+
+pub fn timer_path(archive: &Path) -> PathBuf {
+    archive.join("timer.json")
+}
+
+pub fn write(archive: &Path, timer: &PersistedTimer) -> Result<()> {
+    fs::write(timer_path(archive), serde_json::to_string_pretty(timer)?)?;
+    Ok(())
+}
+
+pub fn clear(archive: &Path) -> Result<()> {
+    let path = timer_path(archive);
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+pub fn read(archive: &Path) -> Option<PersistedTimer> {
+    let content = fs::read_to_string(timer_path(archive)).ok()?;
+    serde_json::from_str(&content).ok()
+}
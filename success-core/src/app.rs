@@ -1,10 +1,20 @@
-use chrono::{Local, NaiveDate};
+use std::collections::{HashMap, HashSet};
 
+use chrono::{Local, NaiveDate, NaiveTime};
+
+use crate::config::{scalar_settings, Config};
+use crate::goal_meta::{self, GoalMeta, GoalMetaMap};
 use crate::handlers::*;
-use crate::key_event::AppKeyEvent;
-use crate::notes::refresh_notes_for_selection;
+use crate::key_event::{AppKeyCode, AppKeyEvent};
+use crate::notes::{
+    find_interrupted_session, refresh_note_snippets, refresh_notes_for_selection,
+    save_notes_for_selection,
+};
+use crate::session_meta::{self, SessionMeta, SessionMetaMap};
+use crate::timer::record_running_timer;
 use crate::types::*;
-use crate::ui::build_view_items;
+use crate::ui::{build_view_items, ViewItemKind};
+use crate::utils::{earliest_session_day, goal_minutes_sparkline, selected_goal_id};
 use successlib::{Goal, SessionView};
 use tui_textarea::TextArea;
 
@@ -14,17 +24,74 @@ pub struct AppState {
     pub goals: Vec<Goal>,
     pub nodes: Vec<SessionView>,
     pub current_day: NaiveDate,
+    /// Local date at the last tick, to notice midnight passing.
+    pub today: NaiveDate,
     pub selected: usize,
     pub mode: Mode,
     pub search_input: TextArea<'static>,
     pub search_selected: usize,
+    /// Order selector results by lifetime total time instead of recency.
+    pub search_sort_by_total: bool,
+    /// Lifetime seconds per goal, cached while the selector is open.
+    pub goal_totals: HashMap<u64, i64>,
+    /// This week's progress per goal with a weekly target, cached while the
+    /// selector is open.
+    pub weekly_progress: HashMap<u64, (String, bool)>,
+    /// Totals of the viewed day, computed when the insights popup opens.
+    pub insights: DayInsights,
     pub duration_input: TextArea<'static>,
     pub quantity_input: TextArea<'static>,
+    pub prompt_input: TextArea<'static>,
     pub timer: Option<TimerState>,
+    /// Don't launch the goal's commands for the next timer (toggled with
+    /// Ctrl+n in the duration dialog).
+    pub skip_commands: bool,
+    /// Always show today and ignore day navigation (the CLI's `kiosk`).
+    pub kiosk: bool,
+    /// Draw bars with ASCII for terminals without Unicode (the CLI's `--ascii`).
+    pub ascii: bool,
     pub pending_session: Option<PendingSession>,
     pub notes_textarea: TextArea<'static>,
+    /// First line shown in the read-only notes view.
+    pub notes_scroll: u16,
+    /// The notes panel has edits that haven't been written yet.
+    pub notes_dirty: bool,
+    /// Goal and day whose notes stay in the notes panel while the selection
+    /// moves (toggled with `L`).
+    pub notes_lock: Option<(u64, NaiveDate)>,
+    /// `config` was changed on the settings screen and should be saved (CLI).
+    pub config_changed: bool,
+    /// Options of the frontend, as a JSON object, listed after `config`'s on
+    /// the settings screen.
+    pub cli_settings: serde_json::Value,
+    /// Check run on `cli_settings` before a change on the settings screen is
+    /// kept; the error is shown in the status line.
+    pub check_cli_settings: Option<fn(&serde_json::Value) -> Result<(), String>>,
+    /// Show only name and duration for each session in the list.
+    pub compact_labels: bool,
+    /// Show only a large countdown while the timer runs (toggled with `f`).
+    pub focus_view: bool,
+    /// The notes panel shows the viewed day's journal instead of goal notes.
+    pub day_notes: bool,
+    /// First line of each session's notes, keyed by goal id and start time.
+    pub note_snippets: HashMap<(u64, i64), String>,
+    /// Group the sessions list under a heading per goal.
+    pub grouped_view: bool,
+    /// Goals whose sessions are hidden in the grouped view.
+    pub collapsed_goals: HashSet<u64>,
     pub focused_block: FocusedBlock,
     pub form_state: Option<FormState>,
+    pub config: Config,
+    pub goal_meta: GoalMetaMap,
+    pub session_meta: SessionMetaMap,
+    /// Transient message shown in the header until the next key press.
+    pub status: Option<String>,
+    /// Day of the earliest recorded session, used to bound day navigation.
+    pub earliest_day: Option<NaiveDate>,
+    /// Last day the insights popup opened automatically at `insights_time`.
+    pub insights_shown_on: Option<NaiveDate>,
+    /// Daily goal minutes leading up to `current_day`, shown in the header.
+    pub sparkline: String,
 }
 
 impl AppState {
@@ -36,48 +103,343 @@ impl AppState {
             today.format("%Y-%m-%d").to_string(),
         )
         .unwrap_or_default();
+        let mut state = Self::from_parts(archive_path, goals, nodes, today);
+        state.earliest_day = earliest_session_day(&state.archive_path);
+        state.goal_meta = goal_meta::load(&state.archive_path);
+        state.session_meta = session_meta::load(&state.archive_path);
+        state.sparkline = goal_minutes_sparkline(&state.archive_path, today);
+        state.selected = build_view_items(&state, 20).len().saturating_sub(1);
+        refresh_notes_for_selection(&mut state);
+        refresh_note_snippets(&mut state);
+        state
+    }
+
+    /// Build a state for `day` from already loaded goals and sessions,
+    /// without reading the archive. Goal settings, notes and the sparkline
+    /// start empty; the last row is selected.
+    pub fn from_parts(
+        archive_path: String,
+        goals: Vec<Goal>,
+        nodes: Vec<SessionView>,
+        day: NaiveDate,
+    ) -> Self {
         let mut state = Self {
             archive_path,
             goals,
             nodes,
-            current_day: today,
+            current_day: day,
+            today: Local::now().date_naive(),
             selected: 0,
             mode: Mode::View,
             search_input: TextArea::default(),
             search_selected: 0,
+            search_sort_by_total: false,
+            goal_totals: HashMap::new(),
+            weekly_progress: HashMap::new(),
+            insights: DayInsights::default(),
             duration_input: TextArea::default(),
             quantity_input: TextArea::default(),
+            prompt_input: TextArea::default(),
             timer: None,
+            skip_commands: false,
+            kiosk: false,
+            ascii: false,
             pending_session: None,
             notes_textarea: TextArea::default(),
+            notes_scroll: 0,
+            notes_dirty: false,
+            notes_lock: None,
+            config_changed: false,
+            cli_settings: serde_json::Value::Null,
+            check_cli_settings: None,
+            compact_labels: false,
+            focus_view: false,
+            day_notes: false,
+            note_snippets: HashMap::new(),
+            grouped_view: false,
+            collapsed_goals: HashSet::new(),
             focused_block: FocusedBlock::SessionsList,
             form_state: None,
+            config: Config::default(),
+            goal_meta: GoalMetaMap::new(),
+            session_meta: SessionMetaMap::new(),
+            status: None,
+            earliest_day: None,
+            insights_shown_on: None,
+            sparkline: String::new(),
         };
         state.selected = build_view_items(&state, 20).len().saturating_sub(1);
-        refresh_notes_for_selection(&mut state);
         state
     }
 
+    /// Rows of the settings screen: `config`'s options, then `cli_settings`.
+    pub fn settings(&self) -> Vec<(String, String)> {
+        let mut rows = self.config.settings();
+        rows.extend(scalar_settings(&self.cli_settings));
+        rows
+    }
+
+    /// Settings for `goal_id`, or the defaults if none were stored.
+    pub fn goal_meta(&self, goal_id: u64) -> GoalMeta {
+        self.goal_meta.get(&goal_id).cloned().unwrap_or_default()
+    }
+
+    /// Store settings for `goal_id` and persist them to the archive.
+    pub fn set_goal_meta(&mut self, goal_id: u64, meta: GoalMeta) {
+        self.goal_meta.insert(goal_id, meta);
+        let _ = goal_meta::save(&self.archive_path, &self.goal_meta);
+    }
+
+    /// Details of the session of `goal_id` started at `start_at`, or the
+    /// defaults if none were stored.
+    pub fn session_meta(&self, goal_id: u64, start_at: i64) -> SessionMeta {
+        self.session_meta
+            .get(&session_meta::key(goal_id, start_at))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Store details of a session and persist them to the archive.
+    pub fn set_session_meta(&mut self, goal_id: u64, start_at: i64, meta: SessionMeta) {
+        self.session_meta
+            .insert(session_meta::key(goal_id, start_at), meta);
+        let _ = session_meta::save(&self.archive_path, &self.session_meta);
+    }
+
+    /// Restore a previously viewed day and selection, e.g. after a restart.
+    /// Days in the future are ignored.
+    pub fn restore_view(
+        &mut self,
+        day: NaiveDate,
+        goal_id: Option<u64>,
+        focused_block: FocusedBlock,
+    ) {
+        if day <= Local::now().date_naive() && day != self.current_day {
+            load_day(self, day);
+        }
+        if let Some(goal_id) = goal_id {
+            let items = build_view_items(self, 20);
+            let found = items.iter().rposition(|item| match item.kind {
+                ViewItemKind::Existing(_, idx) => {
+                    self.nodes.get(idx).map(|n| n.goal_id) == Some(goal_id)
+                }
+                _ => false,
+            });
+            if let Some(idx) = found {
+                self.selected = idx;
+                refresh_notes_for_selection(self);
+            }
+        }
+        if focused_block == FocusedBlock::Notes && selected_goal_id(self).is_some() {
+            self.mode = Mode::NotesEdit;
+            self.focused_block = FocusedBlock::Notes;
+        }
+    }
+
     /// Dispatch a key event to the appropriate handler.
     /// Returns true if the app should quit (only for CLI).
     pub fn handle_key(&mut self, key: AppKeyEvent) -> bool {
         if key.is_ctrl_c() {
             return true;
         }
+        let bare_q = self.config.quit_on_q && matches!(self.mode, Mode::View) && !key.ctrl;
+        if key.code == AppKeyCode::Char('q') && (key.ctrl || bare_q) {
+            self.prepare_quit();
+            return true;
+        }
+        self.status = None;
         match self.mode {
             Mode::View => handle_view_key(self, &key),
-            Mode::AddSession | Mode::AddReward => handle_search_key(self, &key),
+            Mode::AddSession | Mode::AddReward | Mode::AddCheckpoint | Mode::AddBackfill => {
+                handle_search_key(self, &key)
+            }
             Mode::GoalForm => handle_form_key(self, &key),
             Mode::QuantityDoneInput { .. } => handle_quantity_done_key(self, &key),
             Mode::DurationInput { .. } => handle_duration_key(self, &key),
             Mode::Timer => handle_timer_key(self, &key),
             Mode::NotesEdit => handle_notes_key(self, &key),
+            Mode::SessionSummary { .. } => handle_summary_key(self, &key),
+            Mode::Insights => handle_insights_key(self, &key),
+            Mode::GoalHistory { .. } => handle_goal_history_key(self, &key),
+            Mode::GoalPaths { .. } => handle_goal_paths_key(self, &key),
+            Mode::Settings { .. } => handle_settings_key(self, &key),
+            Mode::Prompt { .. } => handle_prompt_key(self, &key),
+            Mode::ConfirmReward { .. } => handle_confirm_reward_key(self, &key),
+            Mode::RecoverSession { .. } => handle_recover_key(self, &key),
         }
         false
     }
 
+    /// Ask for the duration of a session that was started but never recorded,
+    /// if there is one. Call once at startup, after `config` is set.
+    pub fn offer_interrupted_session(&mut self) {
+        let Some((goal_id, started_at)) = find_interrupted_session(self) else {
+            return;
+        };
+        let Some(goal) = self.goals.iter().find(|g| g.id == goal_id) else {
+            return;
+        };
+        let goal_name = goal.name.clone();
+        let is_reward = successlib::search_goals(
+            self.archive_path.clone(),
+            String::new(),
+            Some(true),
+            None,
+            Some(true),
+        )
+        .unwrap_or_default()
+        .iter()
+        .any(|g| g.id == goal_id);
+        clear_single_line_textarea(&mut self.duration_input);
+        self.mode = Mode::RecoverSession {
+            goal_name,
+            goal_id,
+            is_reward,
+            started_at,
+        };
+    }
+
+    /// Save notes being edited and, with `record_on_quit`, log the time the
+    /// running timer has run so far. Used by the graceful quit.
+    fn prepare_quit(&mut self) {
+        if matches!(self.mode, Mode::NotesEdit) {
+            save_notes_for_selection(self);
+        }
+        self.mode = Mode::View;
+        if self.config.record_on_quit {
+            record_running_timer(self);
+        }
+    }
+
     /// Tick the timer (call on every frame / poll cycle).
     pub fn tick(&mut self) {
         crate::timer::tick_timer(self);
+        self.follow_midnight();
+        self.maybe_show_insights();
+    }
+
+    /// Move on to the new day at midnight if today was being viewed. A past
+    /// day the user navigated to stays put. Waits while a dialog or the notes
+    /// editor is open so nothing being edited is reloaded.
+    fn follow_midnight(&mut self) {
+        let today = Local::now().date_naive();
+        if today == self.today || !matches!(self.mode, Mode::View | Mode::Timer) {
+            return;
+        }
+        if self.current_day == self.today || self.kiosk {
+            load_day(self, today);
+            self.status = Some(format!("New day: {}", today.format("%A %Y-%m-%d")));
+        }
+        self.today = today;
+    }
+
+    /// Open today's insights once per day when `insights_time` has passed.
+    fn maybe_show_insights(&mut self) {
+        let Some(at) = self
+            .config
+            .insights_time
+            .as_deref()
+            .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+        else {
+            return;
+        };
+        let now = Local::now();
+        let today = now.date_naive();
+        if now.time() < at
+            || self.insights_shown_on == Some(today)
+            || self.current_day != today
+            || !matches!(self.mode, Mode::View)
+        {
+            return;
+        }
+        self.insights_shown_on = Some(today);
+        open_insights(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: AppKeyCode) -> AppKeyEvent {
+        AppKeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    fn empty_state() -> AppState {
+        AppState::from_parts(
+            "unused-archive".to_string(),
+            vec![],
+            vec![],
+            Local::now().date_naive(),
+        )
+    }
+
+    #[test]
+    fn empty_day_selects_the_add_row() {
+        let state = empty_state();
+        let items = build_view_items(&state, 20);
+        assert_eq!(items.len(), 1);
+        assert!(matches!(items[0].kind, ViewItemKind::AddSession));
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn selection_stays_within_the_list() {
+        let mut state = empty_state();
+        state.handle_key(key(AppKeyCode::Char('j')));
+        assert_eq!(state.selected, 0);
+        state.handle_key(key(AppKeyCode::Char('k')));
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn insights_open_and_close() {
+        let mut state = empty_state();
+        state.handle_key(key(AppKeyCode::Char('i')));
+        assert!(matches!(state.mode, Mode::Insights));
+        state.handle_key(key(AppKeyCode::Esc));
+        assert!(matches!(state.mode, Mode::View));
+    }
+
+    #[test]
+    fn prompt_closes_on_esc() {
+        let mut state = empty_state();
+        state.handle_key(key(AppKeyCode::Char('g')));
+        assert!(matches!(
+            state.mode,
+            Mode::Prompt {
+                action: PromptAction::GoToDay,
+                ..
+            }
+        ));
+        state.handle_key(key(AppKeyCode::Esc));
+        assert!(matches!(state.mode, Mode::View));
+    }
+
+    #[test]
+    fn ctrl_c_quits_from_any_mode() {
+        let mut state = empty_state();
+        state.mode = Mode::Insights;
+        let quit = state.handle_key(AppKeyEvent {
+            ctrl: true,
+            ..key(AppKeyCode::Char('c'))
+        });
+        assert!(quit);
+    }
+
+    #[test]
+    fn bare_q_quits_only_when_enabled() {
+        let mut state = empty_state();
+        assert!(!state.handle_key(key(AppKeyCode::Char('q'))));
+        state.config.quit_on_q = true;
+        state.mode = Mode::Insights;
+        assert!(!state.handle_key(key(AppKeyCode::Char('q'))));
+        state.mode = Mode::View;
+        assert!(state.handle_key(key(AppKeyCode::Char('q'))));
     }
 }
@@ -0,0 +1,226 @@
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::style;
+use crate::types::FormField;
+
+/// User-configurable behaviour shared by the CLI and web builds.
+///
+/// The CLI reads these from `config.json`; the web build uses the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Stop day navigation the day before the earliest recorded session.
+    pub limit_past_navigation: bool,
+    /// Reward time earned per unit of goal time, used for the reward balance.
+    pub reward_ratio: f64,
+    /// Order the goal selector by lifetime total time instead of recency.
+    pub sort_goals_by_total: bool,
+    /// Spaces (0–4) before wrapped continuation lines in the sessions list.
+    pub wrap_indent: usize,
+    /// Local time ("HH:MM") at which today's insights popup opens by itself.
+    pub insights_time: Option<String>,
+    /// What the timer gauge shows as its label.
+    pub gauge_label: GaugeLabel,
+    /// Keep each goal's notes in one file per day instead of a single file.
+    pub notes_per_day: bool,
+    /// Symbols in front of the items in the sessions list.
+    pub prefixes: Prefixes,
+    /// Ignore day navigation keys while a timer is running.
+    pub lock_during_timer: bool,
+    /// Ask "Start reward now?" before the reward duration dialog.
+    pub confirm_reward: bool,
+    /// Hex colour ("#RRGGBB") used instead of the default blue accent.
+    pub accent_color: Option<String>,
+    /// Select the trailing add row after loading a day; when off, the last
+    /// session is selected instead.
+    pub auto_select_add_row: bool,
+    /// Suggest a break once a goal session has run this many minutes.
+    pub break_reminder_mins: Option<u64>,
+    /// Where to go once the quantity of a finished goal session is entered.
+    pub after_quantity: AfterQuantity,
+    /// Show the planned duration next to sessions that were stopped early.
+    pub show_planned: bool,
+    /// Open the notes editor under the new session header when a timer starts.
+    pub notes_on_start: bool,
+    /// Log the running timer's elapsed time when quitting with Ctrl+q.
+    pub record_on_quit: bool,
+    /// Let a bare `q` in the main view quit like Ctrl+q. Off by default so a
+    /// stray keypress can't close the app.
+    pub quit_on_q: bool,
+    /// Explain the prefixes along the bottom border of the sessions list.
+    pub prefix_legend: bool,
+    /// Ask for a 1-5 rating after each goal session is logged.
+    pub rate_sessions: bool,
+    /// Wrap the read-only notes view at this column instead of the panel
+    /// width, e.g. to match an external editor.
+    pub notes_display_width: Option<usize>,
+    /// How the duration dialog's suggestion is picked from past sessions.
+    pub duration_suggestion: DurationSuggestion,
+    /// Recent sessions of the goal that `median` and `common` look at.
+    pub suggestion_sessions: usize,
+    /// Fields of the new goal/reward form, in tab order. Left-out fields
+    /// stay empty; the name is always shown first.
+    pub goal_form_fields: Vec<FormField>,
+    /// Text that makes a new goal's command ask for confirmation, e.g.
+    /// "rm -rf". Commands run through `sh -c`, so this catches copy-paste
+    /// mistakes; it is not a sandbox.
+    pub dangerous_commands: Vec<String>,
+    /// First day of the week for weekly targets, e.g. "monday" or "sunday".
+    pub week_start: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Prefixes {
+    pub session: String,
+    pub reward: String,
+    pub running: String,
+    pub add: String,
+}
+
+impl Default for Prefixes {
+    fn default() -> Self {
+        Self {
+            session: "[S]".to_string(),
+            reward: "[R]".to_string(),
+            running: "[*]".to_string(),
+            add: "[+]".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GaugeLabel {
+    /// Percentage of the session elapsed, e.g. "40%".
+    #[default]
+    Percent,
+    /// Time left, e.g. "14:59 remaining".
+    Remaining,
+    /// Both, e.g. "40% • 14:59 remaining".
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AfterQuantity {
+    /// Back to the sessions list.
+    #[default]
+    View,
+    /// Open the reward selector.
+    Reward,
+    /// Open the goal selector for the next session.
+    Session,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationSuggestion {
+    /// Duration of the goal's latest session.
+    #[default]
+    Latest,
+    /// Median duration of the goal's recent sessions.
+    Median,
+    /// Most common duration of the goal's recent sessions.
+    Common,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            limit_past_navigation: false,
+            reward_ratio: 1.0,
+            sort_goals_by_total: false,
+            wrap_indent: 4,
+            insights_time: None,
+            gauge_label: GaugeLabel::default(),
+            notes_per_day: false,
+            prefixes: Prefixes::default(),
+            lock_during_timer: false,
+            confirm_reward: false,
+            accent_color: None,
+            auto_select_add_row: true,
+            break_reminder_mins: None,
+            after_quantity: AfterQuantity::default(),
+            show_planned: false,
+            notes_on_start: false,
+            record_on_quit: true,
+            quit_on_q: false,
+            prefix_legend: true,
+            rate_sessions: false,
+            notes_display_width: None,
+            duration_suggestion: DurationSuggestion::default(),
+            suggestion_sessions: 5,
+            goal_form_fields: FormField::ORDER.to_vec(),
+            dangerous_commands: [
+                "rm -rf",
+                "rm -fr",
+                "mkfs",
+                "dd if=",
+                ":(){",
+                "shutdown",
+                "reboot",
+                "> /dev/sd",
+            ]
+            .map(String::from)
+            .to_vec(),
+            week_start: "monday".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Colour for highlights, focused borders and the timer gauge.
+    pub fn accent(&self) -> Color {
+        self.accent_color
+            .as_deref()
+            .and_then(style::parse_hex)
+            .unwrap_or(style::BLUE)
+    }
+
+    /// Options with a single value, as `(key, value)` rows for the settings
+    /// screen. Lists and the prefixes are left to `config.json`.
+    pub fn settings(&self) -> Vec<(String, String)> {
+        serde_json::to_value(self)
+            .map(|value| scalar_settings(&value))
+            .unwrap_or_default()
+    }
+
+    /// This config with `key` set from `input` (see [`set_setting`]).
+    pub fn with_setting(&self, key: &str, input: &str) -> Result<Config, String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        set_setting(&mut value, key, input);
+        serde_json::from_value(value).map_err(|e| format!("Invalid {key}: {e}"))
+    }
+}
+
+/// The keys of the JSON object `value` that hold a single value, as
+/// `(key, value)` rows; null is shown as blank.
+pub fn scalar_settings(value: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(map) = value.as_object() else {
+        return Vec::new();
+    };
+    map.iter()
+        .filter_map(|(key, value)| {
+            let shown = match value {
+                serde_json::Value::Null => String::new(),
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+                _ => return None,
+            };
+            Some((key.clone(), shown))
+        })
+        .collect()
+}
+
+/// Set `key` of the JSON object `value` from `input`: JSON such as `true` or
+/// `1.5`, other text as a string, and blank for none.
+pub fn set_setting(value: &mut serde_json::Value, key: &str, input: &str) {
+    let input = input.trim();
+    value[key] = if input.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(input).unwrap_or_else(|_| serde_json::Value::String(input.into()))
+    };
+}
@@ -0,0 +1,91 @@
+//! Per-goal settings that success-lib's `Goal` doesn't store.
+//!
+//! Kept in `goal_meta.json` inside the archive. The web build keeps them in
+//! memory only, like the rest of its data.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoalMeta {
+    /// Duration pre-filled in the duration dialog, e.g. "45m".
+    pub default_duration: Option<String>,
+    /// Time to spend on the goal each day, e.g. "1h".
+    pub daily_target: Option<String>,
+    /// Time ("3h") or number of sessions ("5x") to do each week.
+    pub weekly_target: Option<String>,
+    /// Listed first in the goal selector when the query is empty.
+    pub pinned: bool,
+    /// Close the goal's apps when its timer ends (CLI only).
+    pub kill_on_end: bool,
+    /// Multiplier for this reward's time when computing the reward balance.
+    pub reward_weight: Option<f32>,
+    /// Quantities may be negative, e.g. a change in weight. success-lib only
+    /// stores `u32`, so they are kept as the bits of an `i32`.
+    pub allow_negative: bool,
+    /// Enter saves and leaves the in-app notes editor instead of starting a
+    /// new line, for goals whose notes are one-line reminders.
+    pub single_line_notes: bool,
+    /// Singular of the goal's quantity name, e.g. "mouse" for "mice". Without
+    /// it a trailing "s" is dropped.
+    pub quantity_singular: Option<String>,
+    /// Ask for the quantity done when a timer for the goal ends. When false,
+    /// sessions are logged without one unless asked for with `Q`.
+    pub prompt_quantity: bool,
+}
+
+impl Default for GoalMeta {
+    fn default() -> Self {
+        Self {
+            default_duration: None,
+            daily_target: None,
+            weekly_target: None,
+            pinned: false,
+            kill_on_end: true,
+            reward_weight: None,
+            allow_negative: false,
+            single_line_notes: false,
+            quantity_singular: None,
+            prompt_quantity: true,
+        }
+    }
+}
+
+impl GoalMeta {
+    pub fn reward_weight(&self) -> f32 {
+        self.reward_weight.unwrap_or(1.0)
+    }
+}
+
+pub type GoalMetaMap = HashMap<u64, GoalMeta>;
+
+#[cfg(not(feature = "web"))]
+pub fn meta_path(archive_path: &str) -> std::path::PathBuf {
+    std::path::Path::new(archive_path).join("goal_meta.json")
+}
+
+#[cfg(not(feature = "web"))]
+pub fn load(archive_path: &str) -> GoalMetaMap {
+    std::fs::read_to_string(meta_path(archive_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "web")]
+pub fn load(_archive_path: &str) -> GoalMetaMap {
+    GoalMetaMap::new()
+}
+
+#[cfg(not(feature = "web"))]
+pub fn save(archive_path: &str, meta: &GoalMetaMap) -> std::io::Result<()> {
+    let content = serde_json::to_string_pretty(meta).map_err(std::io::Error::other)?;
+    std::fs::write(meta_path(archive_path), content)
+}
+
+#[cfg(feature = "web")]
+pub fn save(_archive_path: &str, _meta: &GoalMetaMap) -> std::io::Result<()> {
+    Ok(())
+}
@@ -1,20 +1,46 @@
-use chrono::{Duration as ChronoDuration, Local};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveTime, TimeZone};
 
 use crate::app::AppState;
+use crate::config::{set_setting, AfterQuantity, DurationSuggestion};
 use crate::key_event::{AppKeyCode, AppKeyEvent};
-use crate::notes::{refresh_notes_for_selection, save_notes_for_selection};
-use crate::timer::{finalize_session, start_timer};
+use crate::notes::{
+    load_note, mark_not_logged, migrate_to_per_day, notes_target, refresh_note_snippets,
+    refresh_notes_for_selection, save_notes_for_selection, show_day_notes, store_note,
+};
+use crate::timer::{
+    complete_session, finalize_session, log_checkpoint, start_timer, stop_timer_early,
+    toggle_quantity_prompt,
+};
 use crate::types::*;
-use crate::ui::{build_view_items, ViewItemKind};
+use crate::ui::{build_view_items, ViewItem, ViewItemKind};
 use crate::utils::{
-    format_duration_suggestion, parse_commands_input, parse_duration, parse_optional_u32,
-    selected_goal_id,
+    affordable_reward_secs, day_insights, default_selection, format_duration_suggestion,
+    format_secs_short, goal_minutes_sparkline, goal_total_secs, parse_commands_input,
+    parse_duration, parse_quantity, parse_relative_date, parse_weekly_target, selected_goal_id,
+    unmet_daily_targets, weekly_progress,
 };
 use tui_textarea::TextArea;
 
+fn is_day_navigation_key(key: &AppKeyEvent) -> bool {
+    matches!(
+        key.code,
+        AppKeyCode::Left
+            | AppKeyCode::Right
+            | AppKeyCode::Char('h')
+            | AppKeyCode::Char('l')
+            | AppKeyCode::Char('g')
+    )
+}
+
 pub fn handle_view_key(state: &mut AppState, key: &AppKeyEvent) {
+    if state.kiosk && is_day_navigation_key(key) {
+        state.status = Some("Day navigation is off in kiosk mode".to_string());
+        return;
+    }
     match key.code {
-        AppKeyCode::Char('q') => {} // Quit is handled by the caller
+        AppKeyCode::Char('q') => {} // Quits in `AppState::handle_key` with `quit_on_q`
         AppKeyCode::Up | AppKeyCode::Char('k') => {
             let prev = state.selected;
             state.selected = state.selected.saturating_sub(1);
@@ -36,8 +62,62 @@ pub fn handle_view_key(state: &mut AppState, key: &AppKeyEvent) {
                 refresh_notes_for_selection(state);
             }
         }
+        AppKeyCode::Char('n') => select_same_goal_session(state, true),
+        AppKeyCode::Char('N') => select_same_goal_session(state, false),
+        AppKeyCode::Char('i') => open_insights(state),
+        AppKeyCode::Char('H') => open_goal_history(state),
+        AppKeyCode::Char(',') => {
+            state.mode = Mode::Settings {
+                selected: 0,
+                editing: false,
+            };
+        }
+        AppKeyCode::Char('c') => {
+            state.compact_labels = !state.compact_labels;
+        }
+        AppKeyCode::Char('G') => {
+            state.grouped_view = !state.grouped_view;
+            state.selected = default_selection(state);
+            refresh_notes_for_selection(state);
+        }
+        AppKeyCode::Char('g') => {
+            open_prompt(
+                state,
+                "Go to day (e.g. -3, yesterday, monday, 2024-01, 2024-01-15)".to_string(),
+                PromptAction::GoToDay,
+            );
+        }
+        AppKeyCode::Char('a') => {
+            let Some(goal_id) = selected_goal_id(state) else {
+                return;
+            };
+            let name = goal_name(state, goal_id);
+            open_prompt(
+                state,
+                format!("Append to notes of {name}"),
+                PromptAction::AppendNote { goal_id },
+            );
+        }
+        // The web build has nowhere to keep a journal.
+        AppKeyCode::Char('J') if cfg!(not(feature = "web")) => {
+            show_day_notes(state);
+            state.mode = Mode::NotesEdit;
+            state.focused_block = FocusedBlock::Notes;
+        }
+        AppKeyCode::Char('x') => {
+            if state.timer.is_none() {
+                open_goal_selector(state, Mode::AddCheckpoint);
+            }
+        }
+        AppKeyCode::Char('I') => toggle_interrupted(state),
+        AppKeyCode::Char('b') => {
+            if state.timer.is_none() {
+                open_goal_selector(state, Mode::AddBackfill);
+            }
+        }
+        AppKeyCode::Char('L') => toggle_notes_lock(state),
         AppKeyCode::Char('e') => {
-            if selected_goal_id(state).is_some() {
+            if notes_target(state).is_some() {
                 refresh_notes_for_selection(state);
                 state.mode = Mode::NotesEdit;
                 state.focused_block = FocusedBlock::Notes;
@@ -53,17 +133,18 @@ pub fn handle_view_key(state: &mut AppState, key: &AppKeyEvent) {
                     if state.timer.is_some() {
                         return;
                     }
-                    state.mode = Mode::AddSession;
-                    clear_single_line_textarea(&mut state.search_input);
-                    state.search_selected = 0;
+                    open_goal_selector(state, Mode::AddSession);
                 }
                 ViewItemKind::AddReward => {
                     if state.timer.is_some() {
                         return;
                     }
-                    state.mode = Mode::AddReward;
-                    clear_single_line_textarea(&mut state.search_input);
-                    state.search_selected = 0;
+                    open_goal_selector(state, Mode::AddReward);
+                }
+                ViewItemKind::GoalHeader(goal_id) => {
+                    if !state.collapsed_goals.remove(&goal_id) {
+                        state.collapsed_goals.insert(goal_id);
+                    }
                 }
                 ViewItemKind::RunningTimer => {}
                 ViewItemKind::Existing(_, _) => {}
@@ -73,6 +154,68 @@ pub fn handle_view_key(state: &mut AppState, key: &AppKeyEvent) {
     }
 }
 
+/// Keep the shown notes in the notes panel while browsing other goals, or
+/// release them.
+fn toggle_notes_lock(state: &mut AppState) {
+    if state.notes_lock.take().is_some() {
+        state.status = Some("Notes unlocked".to_string());
+    } else if let Some(goal_id) = selected_goal_id(state).filter(|_| !state.day_notes) {
+        state.notes_lock = Some((goal_id, state.current_day));
+        state.status = Some(format!("Notes locked to {}", goal_name(state, goal_id)));
+    }
+    refresh_notes_for_selection(state);
+}
+
+/// Flag the selected session, or the running timer, as interrupted or clear
+/// the flag. The running timer's flag is keyed by its start, which becomes the
+/// start of the logged session.
+fn toggle_interrupted(state: &mut AppState) {
+    let items = build_view_items(state, 20);
+    let key = match items.get(state.selected).map(|item| item.kind) {
+        Some(ViewItemKind::Existing(_, idx)) => {
+            state.nodes.get(idx).map(|n| (n.goal_id, n.start_at))
+        }
+        Some(ViewItemKind::RunningTimer) => state
+            .timer
+            .as_ref()
+            .map(|t| (t.goal_id, t.started_at.timestamp())),
+        _ => None,
+    };
+    let Some((goal_id, start_at)) = key else {
+        return;
+    };
+    let mut meta = state.session_meta(goal_id, start_at);
+    meta.interrupted = !meta.interrupted;
+    state.status = Some(
+        if meta.interrupted {
+            "Marked as interrupted"
+        } else {
+            "No longer marked as interrupted"
+        }
+        .to_string(),
+    );
+    state.set_session_meta(goal_id, start_at, meta);
+}
+
+/// Enter the goal/reward selector (`Mode::AddSession`, `Mode::AddReward`,
+/// `Mode::AddCheckpoint` or `Mode::AddBackfill`).
+pub fn open_goal_selector(state: &mut AppState, mode: Mode) {
+    state.mode = mode;
+    clear_single_line_textarea(&mut state.search_input);
+    state.search_selected = 0;
+    state.search_sort_by_total = state.config.sort_goals_by_total;
+    state.goal_totals = if state.search_sort_by_total {
+        goal_total_secs(&state.archive_path)
+    } else {
+        HashMap::new()
+    };
+    state.weekly_progress = if matches!(state.mode, Mode::AddReward) {
+        HashMap::new()
+    } else {
+        weekly_progress(state)
+    };
+}
+
 pub fn shift_day(state: &mut AppState, delta: i64) {
     if delta == 0 {
         return;
@@ -87,17 +230,94 @@ pub fn shift_day(state: &mut AppState, delta: i64) {
     if new_day > today {
         return;
     }
-    state.current_day = new_day;
+    go_to_day(state, new_day);
+}
+
+/// Load `day` if it may be viewed, otherwise explain why in the status.
+pub fn go_to_day(state: &mut AppState, day: NaiveDate) {
+    let today = Local::now().date_naive();
+    if day > today {
+        state.status = Some("Can't view a future day".to_string());
+        return;
+    }
+    // The day before the first session stays reachable so a forgotten
+    // session can be backfilled there, which moves the limit back a day.
+    let limit = state
+        .earliest_day
+        .and_then(|earliest| earliest.pred_opt())
+        .filter(|_| state.config.limit_past_navigation);
+    if limit.is_some_and(|limit| day < limit) {
+        state.status = Some("No sessions recorded before this day".to_string());
+        return;
+    }
+    load_day(state, day);
+}
+
+/// Move the selection to the next (or previous) session of the selected
+/// session's goal, wrapping around at the ends of the day.
+fn select_same_goal_session(state: &mut AppState, forward: bool) {
+    let items = build_view_items(state, 20);
+    let node_idx = |item: &ViewItem| match item.kind {
+        ViewItemKind::Existing(_, idx) => Some(idx),
+        _ => None,
+    };
+    let Some(goal_id) = items
+        .get(state.selected)
+        .and_then(node_idx)
+        .and_then(|idx| state.nodes.get(idx))
+        .map(|n| n.goal_id)
+    else {
+        return;
+    };
+    let matching: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            node_idx(item)
+                .and_then(|idx| state.nodes.get(idx))
+                .is_some_and(|n| n.goal_id == goal_id)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    let next = if forward {
+        matching
+            .iter()
+            .find(|&&i| i > state.selected)
+            .or(matching.first())
+    } else {
+        matching
+            .iter()
+            .rev()
+            .find(|&&i| i < state.selected)
+            .or(matching.last())
+    };
+    if let Some(&next) = next {
+        if next != state.selected {
+            state.selected = next;
+            refresh_notes_for_selection(state);
+        }
+    }
+}
+
+/// Switch the view to `day`, reloading its sessions and selecting the last item.
+pub fn load_day(state: &mut AppState, day: NaiveDate) {
+    state.current_day = day;
     state.nodes = successlib::list_day_sessions(
         state.archive_path.clone(),
-        new_day.format("%Y-%m-%d").to_string(),
+        day.format("%Y-%m-%d").to_string(),
     )
     .unwrap_or_default();
-    state.selected = build_view_items(state, 20).len().saturating_sub(1);
+    state.sparkline = goal_minutes_sparkline(&state.archive_path, day);
+    state.selected = default_selection(state);
     refresh_notes_for_selection(state);
+    refresh_note_snippets(state);
 }
 
 pub fn handle_search_key(state: &mut AppState, key: &AppKeyEvent) {
+    if key.ctrl && key.code == AppKeyCode::Char('p') {
+        toggle_selected_pin(state);
+        return;
+    }
     if handle_single_line_textarea_key(&mut state.search_input, key) {
         state.search_selected = 0;
         return;
@@ -119,38 +339,53 @@ pub fn handle_search_key(state: &mut AppState, key: &AppKeyEvent) {
                             current_field: FormField::GoalName,
                             goal_name: single_line_textarea_from_string(name.clone()),
                             quantity_name: TextArea::default(),
+                            allow_negative: TextArea::default(),
+                            default_duration: TextArea::default(),
+                            daily_target: TextArea::default(),
+                            weekly_target: TextArea::default(),
+                            reward_weight: TextArea::default(),
                             commands: TextArea::default(),
                             is_reward: *is_reward,
+                            order: state.config.goal_form_fields.clone(),
+                            danger_confirmed: false,
                         });
                         state.mode = Mode::GoalForm;
                     }
+                    SearchResult::Existing(goal) if matches!(state.mode, Mode::AddCheckpoint) => {
+                        log_checkpoint(state, goal.name.clone(), goal.id);
+                    }
+                    SearchResult::Existing(goal) if matches!(state.mode, Mode::AddBackfill) => {
+                        open_prompt(
+                            state,
+                            format!(
+                                "Start time of {} on {} (HH:MM)",
+                                goal.name,
+                                state.current_day.format("%Y-%m-%d")
+                            ),
+                            PromptAction::BackfillStart { goal_id: goal.id },
+                        );
+                    }
                     SearchResult::Existing(goal) => {
-                        let mut suggestion = None;
-                        let recent = successlib::list_sessions_between_dates(
-                            state.archive_path.clone(),
-                            None,
-                            None,
-                        )
-                        .unwrap_or_default();
-                        if let Some(last) = recent
-                            .iter()
-                            .filter(|s| s.goal_id == goal.id)
-                            .max_by_key(|s| s.start_at)
-                        {
-                            let duration_mins = (last.end_at - last.start_at) / 60;
-                            suggestion = Some(format_duration_suggestion(duration_mins));
+                        let is_reward = matches!(state.mode, Mode::AddReward);
+                        if is_reward && state.config.confirm_reward {
+                            state.mode = Mode::ConfirmReward {
+                                goal_name: goal.name.clone(),
+                                goal_id: goal.id,
+                            };
+                        } else {
+                            open_duration_input(state, is_reward, goal.name.clone(), goal.id);
                         }
-                        let suggestion = suggestion.unwrap_or_else(|| "25m".to_string());
-                        state.duration_input = single_line_textarea_from_string(suggestion);
-                        state.mode = Mode::DurationInput {
-                            is_reward: matches!(state.mode, Mode::AddReward),
-                            goal_name: goal.name.clone(),
-                            goal_id: goal.id,
-                        };
                     }
                 }
             }
         }
+        AppKeyCode::Tab => {
+            state.search_sort_by_total = !state.search_sort_by_total;
+            if state.search_sort_by_total && state.goal_totals.is_empty() {
+                state.goal_totals = goal_total_secs(&state.archive_path);
+            }
+            state.search_selected = 0;
+        }
         AppKeyCode::Up => {
             if state.search_selected > 0 {
                 state.search_selected -= 1;
@@ -166,19 +401,133 @@ pub fn handle_search_key(state: &mut AppState, key: &AppKeyEvent) {
     }
 }
 
+fn toggle_selected_pin(state: &mut AppState) {
+    let results = search_results(state);
+    let Some((_, SearchResult::Existing(goal))) = results.get(state.search_selected) else {
+        return;
+    };
+    let mut meta = state.goal_meta(goal.id);
+    meta.pinned = !meta.pinned;
+    state.status = Some(if meta.pinned {
+        format!("Pinned {}", goal.name)
+    } else {
+        format!("Unpinned {}", goal.name)
+    });
+    let goal_id = goal.id;
+    state.set_goal_meta(goal_id, meta);
+    // Keep the selection on the goal that just moved.
+    state.search_selected = search_results(state)
+        .iter()
+        .position(|(_, r)| matches!(r, SearchResult::Existing(g) if g.id == goal_id))
+        .unwrap_or(0);
+}
+
+fn open_duration_input(state: &mut AppState, is_reward: bool, goal_name: String, goal_id: u64) {
+    let suggestion = suggested_duration(state, goal_id);
+    state.duration_input = single_line_textarea_from_string(suggestion);
+    state.mode = Mode::DurationInput {
+        is_reward,
+        goal_name,
+        goal_id,
+    };
+}
+
+pub fn handle_confirm_reward_key(state: &mut AppState, key: &AppKeyEvent) {
+    let Mode::ConfirmReward {
+        ref goal_name,
+        goal_id,
+    } = state.mode
+    else {
+        return;
+    };
+    match key.code {
+        AppKeyCode::Char('y') | AppKeyCode::Char('Y') => {
+            let goal_name = goal_name.clone();
+            open_duration_input(state, true, goal_name, goal_id);
+        }
+        AppKeyCode::Char('n') | AppKeyCode::Char('N') | AppKeyCode::Enter | AppKeyCode::Esc => {
+            state.mode = Mode::View;
+        }
+        _ => {}
+    }
+}
+
+/// Pre-filled value for the duration dialog: the goal's default duration if
+/// set, otherwise the duration of its most recent session.
+fn suggested_duration(state: &AppState, goal_id: u64) -> String {
+    if let Some(default_duration) = state.goal_meta(goal_id).default_duration {
+        return default_duration;
+    }
+    let mut recent: Vec<_> =
+        successlib::list_sessions_between_dates(state.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.goal_id == goal_id)
+            .collect();
+    recent.sort_by_key(|s| std::cmp::Reverse(s.start_at));
+    let minutes: Vec<i64> = recent
+        .iter()
+        .take(state.config.suggestion_sessions.max(1))
+        .map(|s| (s.end_at - s.start_at) / 60)
+        .collect();
+    typical_minutes(&minutes, state.config.duration_suggestion)
+        .map(format_duration_suggestion)
+        .unwrap_or_else(|| "25m".to_string())
+}
+
+/// Pick the suggested duration from session minutes, newest first. Ties for
+/// the most common duration go to the most recent one.
+fn typical_minutes(newest_first: &[i64], how: DurationSuggestion) -> Option<i64> {
+    match how {
+        DurationSuggestion::Latest => newest_first.first().copied(),
+        DurationSuggestion::Median => {
+            let mut sorted = newest_first.to_vec();
+            sorted.sort_unstable();
+            sorted.get(sorted.len().checked_sub(1)? / 2).copied()
+        }
+        DurationSuggestion::Common => {
+            let count = |m: i64| newest_first.iter().filter(|&&other| other == m).count();
+            newest_first
+                .iter()
+                .enumerate()
+                .max_by_key(|&(idx, &m)| (count(m), std::cmp::Reverse(idx)))
+                .map(|(_, &m)| m)
+        }
+    }
+}
+
+/// First entry of `denylist` found in any of `commands`.
+fn dangerous_pattern<'a>(denylist: &'a [String], commands: &[String]) -> Option<&'a str> {
+    denylist
+        .iter()
+        .find(|pattern| {
+            !pattern.is_empty() && commands.iter().any(|cmd| cmd.contains(pattern.as_str()))
+        })
+        .map(String::as_str)
+}
+
+/// Trimmed duration from an optional form field: `Ok(None)` when blank and
+/// `Err` with the input when it isn't a valid duration.
+fn optional_duration(textarea: &TextArea<'_>) -> Result<Option<String>, String> {
+    let value = single_line_textarea_value(textarea).trim().to_string();
+    if value.is_empty() {
+        Ok(None)
+    } else if parse_duration(&value).is_some() {
+        Ok(Some(value))
+    } else {
+        Err(value)
+    }
+}
+
 pub fn handle_form_key(state: &mut AppState, key: &AppKeyEvent) {
     let Some(form) = state.form_state.as_mut() else {
         state.mode = Mode::View;
         return;
     };
 
-    let field = match form.current_field {
-        FormField::GoalName => &mut form.goal_name,
-        FormField::Quantity => &mut form.quantity_name,
-        FormField::Commands => &mut form.commands,
-    };
-
-    if handle_single_line_textarea_key(field, key) {
+    let current_field = form.current_field;
+    if handle_single_line_textarea_key(form.field_mut(current_field), key) {
+        form.danger_confirmed = false;
         return;
     }
 
@@ -188,18 +537,10 @@ pub fn handle_form_key(state: &mut AppState, key: &AppKeyEvent) {
             state.mode = Mode::View;
         }
         AppKeyCode::Up | AppKeyCode::BackTab => {
-            form.current_field = match form.current_field {
-                FormField::GoalName => FormField::Commands,
-                FormField::Quantity => FormField::GoalName,
-                FormField::Commands => FormField::Quantity,
-            };
+            form.focus_prev();
         }
         AppKeyCode::Down | AppKeyCode::Tab => {
-            form.current_field = match form.current_field {
-                FormField::GoalName => FormField::Quantity,
-                FormField::Quantity => FormField::Commands,
-                FormField::Commands => FormField::GoalName,
-            };
+            form.focus_next();
         }
         AppKeyCode::Enter => {
             let goal_name_input = single_line_textarea_value(&form.goal_name);
@@ -208,10 +549,79 @@ pub fn handle_form_key(state: &mut AppState, key: &AppKeyEvent) {
                 return;
             }
 
+            let default_duration = match optional_duration(&form.default_duration) {
+                Ok(value) => value,
+                Err(invalid) => {
+                    form.current_field = FormField::DefaultDuration;
+                    state.status = Some(format!("Invalid default duration: {invalid}"));
+                    return;
+                }
+            };
+            let daily_target = match optional_duration(&form.daily_target) {
+                Ok(value) => value,
+                Err(invalid) => {
+                    form.current_field = FormField::DailyTarget;
+                    state.status = Some(format!("Invalid daily target: {invalid}"));
+                    return;
+                }
+            };
+            let weekly_target = match single_line_textarea_value(&form.weekly_target).trim() {
+                "" => None,
+                value if parse_weekly_target(value).is_some() => Some(value.to_string()),
+                value => {
+                    form.current_field = FormField::WeeklyTarget;
+                    state.status = Some(format!("Invalid weekly target: {value}"));
+                    return;
+                }
+            };
+            let weight_input = single_line_textarea_value(&form.reward_weight);
+            let reward_weight = match weight_input.trim() {
+                "" => None,
+                value => match value.parse::<f32>() {
+                    Ok(weight) if weight >= 0.0 => Some(weight),
+                    _ => {
+                        form.current_field = FormField::RewardWeight;
+                        state.status = Some(format!("Invalid weight: {value}"));
+                        return;
+                    }
+                },
+            };
+
+            let allow_negative = match single_line_textarea_value(&form.allow_negative)
+                .trim()
+                .to_lowercase()
+                .as_str()
+            {
+                "" | "n" | "no" => false,
+                "y" | "yes" => true,
+                value => {
+                    form.current_field = FormField::AllowNegative;
+                    state.status = Some(format!("Expected y or n, got {value:?}"));
+                    return;
+                }
+            };
+
             let commands_input = single_line_textarea_value(&form.commands);
             let quantity_input = single_line_textarea_value(&form.quantity_name);
 
-            let commands = parse_commands_input(&commands_input);
+            // Commands never run in the web build, so don't store them there.
+            let commands = if cfg!(feature = "web") {
+                Vec::new()
+            } else {
+                parse_commands_input(&commands_input)
+            };
+            if !form.danger_confirmed {
+                if let Some(pattern) =
+                    dangerous_pattern(&state.config.dangerous_commands, &commands)
+                {
+                    form.danger_confirmed = true;
+                    form.current_field = FormField::Commands;
+                    state.status = Some(format!(
+                        "A command contains {pattern:?}; press Enter again to save it anyway"
+                    ));
+                    return;
+                }
+            }
             let quantity_name = if quantity_input.trim().is_empty() {
                 None
             } else {
@@ -219,18 +629,39 @@ pub fn handle_form_key(state: &mut AppState, key: &AppKeyEvent) {
             };
             let is_reward = form.is_reward;
 
-            let created = successlib::add_goal(
+            let created = match successlib::add_goal(
                 state.archive_path.clone(),
                 name.clone(),
                 is_reward,
                 commands,
                 quantity_name,
-            )
-            .expect("Failed to add goal");
+            ) {
+                Ok(created) => created,
+                Err(e) => {
+                    state.status = Some(format!("Failed to add {name}: {e:?}"));
+                    return;
+                }
+            };
             state.goals.push(created.clone());
+            if default_duration.is_some()
+                || daily_target.is_some()
+                || weekly_target.is_some()
+                || reward_weight.is_some()
+                || allow_negative
+            {
+                let mut meta = state.goal_meta(created.id);
+                meta.default_duration = default_duration.clone();
+                meta.daily_target = daily_target;
+                meta.weekly_target = weekly_target;
+                meta.reward_weight = reward_weight;
+                meta.allow_negative = allow_negative;
+                state.set_goal_meta(created.id, meta);
+            }
 
             state.form_state = None;
-            state.duration_input = single_line_textarea_from_string("25m".to_string());
+            state.duration_input = single_line_textarea_from_string(
+                default_duration.unwrap_or_else(|| "25m".to_string()),
+            );
             state.mode = Mode::DurationInput {
                 is_reward,
                 goal_name: created.name.clone(),
@@ -242,12 +673,17 @@ pub fn handle_form_key(state: &mut AppState, key: &AppKeyEvent) {
 }
 
 pub fn handle_duration_key(state: &mut AppState, key: &AppKeyEvent) {
+    if key.ctrl && key.code == AppKeyCode::Char('n') {
+        state.skip_commands = !state.skip_commands;
+        return;
+    }
     if handle_single_line_textarea_key(&mut state.duration_input, key) {
         return;
     }
     match key.code {
         AppKeyCode::Esc => {
             clear_single_line_textarea(&mut state.duration_input);
+            state.skip_commands = false;
             state.mode = Mode::View;
         }
         AppKeyCode::Enter => {
@@ -263,10 +699,233 @@ pub fn handle_duration_key(state: &mut AppState, key: &AppKeyEvent) {
             let secs = parse_duration(&duration_value).unwrap_or(25 * 60);
             start_timer(state, goal_name, goal_id, secs as u32, is_reward);
         }
+        AppKeyCode::Tab => {
+            let Mode::DurationInput {
+                is_reward: true,
+                goal_id,
+                ..
+            } = state.mode
+            else {
+                return;
+            };
+            let balance = affordable_reward_secs(state, goal_id);
+            if balance < 60 {
+                state.status = Some("No reward time banked today".to_string());
+                return;
+            }
+            state.duration_input =
+                single_line_textarea_from_string(format_duration_suggestion(balance / 60));
+        }
+        AppKeyCode::Up => step_duration_input(state, DURATION_STEP_MINS),
+        AppKeyCode::Down => step_duration_input(state, -DURATION_STEP_MINS),
         _ => {}
     }
 }
 
+/// Minutes added or removed by Up/Down in the duration dialog.
+const DURATION_STEP_MINS: i64 = 5;
+
+fn step_duration_input(state: &mut AppState, delta_mins: i64) {
+    let current = single_line_textarea_value(&state.duration_input);
+    let mins = parse_duration(&current).map_or(25, |secs| secs as i64 / 60);
+    let stepped = (mins + delta_mins).max(1);
+    state.duration_input = single_line_textarea_from_string(format_duration_suggestion(stepped));
+}
+
+pub fn handle_recover_key(state: &mut AppState, key: &AppKeyEvent) {
+    let Mode::RecoverSession {
+        ref goal_name,
+        goal_id,
+        is_reward,
+        started_at,
+    } = state.mode
+    else {
+        return;
+    };
+    if handle_single_line_textarea_key(&mut state.duration_input, key) {
+        return;
+    }
+    match key.code {
+        AppKeyCode::Esc => {
+            if let Err(message) = mark_not_logged(state, goal_id, started_at) {
+                state.status = Some(message);
+            }
+            clear_single_line_textarea(&mut state.duration_input);
+            state.mode = Mode::View;
+        }
+        AppKeyCode::Enter => {
+            let value = single_line_textarea_value(&state.duration_input);
+            let Some(secs) = parse_duration(&value) else {
+                state.status = Some(format!("Invalid duration: {}", value.trim()));
+                return;
+            };
+            let pending = PendingSession {
+                label: goal_name.clone(),
+                goal_id,
+                total: secs,
+                duration_override: None,
+                is_reward,
+                started_at,
+                is_checkpoint: false,
+            };
+            clear_single_line_textarea(&mut state.duration_input);
+            complete_session(state, pending);
+        }
+        AppKeyCode::Up => step_duration_input(state, DURATION_STEP_MINS),
+        AppKeyCode::Down => step_duration_input(state, -DURATION_STEP_MINS),
+        _ => {}
+    }
+}
+
+/// Open a one-line prompt; its value is handled by `submit_prompt`.
+fn open_prompt(state: &mut AppState, title: String, action: PromptAction) {
+    clear_single_line_textarea(&mut state.prompt_input);
+    state.mode = Mode::Prompt { title, action };
+}
+
+fn close_prompt(state: &mut AppState) {
+    clear_single_line_textarea(&mut state.prompt_input);
+    state.mode = if state.timer.is_some() {
+        Mode::Timer
+    } else {
+        Mode::View
+    };
+}
+
+pub fn handle_prompt_key(state: &mut AppState, key: &AppKeyEvent) {
+    let Mode::Prompt { action, .. } = state.mode else {
+        return;
+    };
+    if handle_single_line_textarea_key(&mut state.prompt_input, key) {
+        return;
+    }
+    match key.code {
+        AppKeyCode::Esc => {
+            close_prompt(state);
+            if let PromptAction::RateSession {
+                after_quantity: true,
+                ..
+            } = action
+            {
+                open_after_quantity_selector(state);
+            }
+        }
+        AppKeyCode::Enter => {
+            let input = single_line_textarea_value(&state.prompt_input);
+            match submit_prompt(state, action, input.trim()) {
+                Ok(()) => {
+                    // A submitted prompt may have opened the next one.
+                    if matches!(state.mode, Mode::Prompt { action: current, .. } if current == action)
+                    {
+                        close_prompt(state);
+                    }
+                }
+                Err(message) => state.status = Some(message),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Act on a prompt's value. An `Err` keeps the prompt open and is shown as
+/// the status.
+fn submit_prompt(state: &mut AppState, action: PromptAction, input: &str) -> Result<(), String> {
+    match action {
+        PromptAction::GoToDay => {
+            let today = Local::now().date_naive();
+            let day = parse_relative_date(input, today)
+                .ok_or_else(|| format!("Unrecognised date: {input}"))?;
+            close_prompt(state);
+            go_to_day(state, day);
+        }
+        PromptAction::AppendNote { goal_id } => {
+            if input.is_empty() {
+                return Ok(());
+            }
+            let mut note = load_note(state, goal_id, state.current_day);
+            if !note.is_empty() && !note.ends_with('\n') {
+                note.push('\n');
+            }
+            note.push_str(input);
+            note.push('\n');
+            store_note(state, goal_id, state.current_day, note)?;
+            refresh_notes_for_selection(state);
+            refresh_note_snippets(state);
+        }
+        PromptAction::BackfillStart { goal_id } => {
+            let time = NaiveTime::parse_from_str(input, "%H:%M")
+                .map_err(|_| format!("Expected a time like 09:30, got {input:?}"))?;
+            let start = Local
+                .from_local_datetime(&state.current_day.and_time(time))
+                .earliest()
+                .ok_or_else(|| format!("{input} doesn't exist on this day"))?;
+            if start > Local::now() {
+                return Err(format!("{input} is in the future"));
+            }
+            let name = goal_name(state, goal_id);
+            let suggestion = suggested_duration(state, goal_id);
+            open_prompt(
+                state,
+                format!("Duration of {name} from {input}"),
+                PromptAction::BackfillDuration {
+                    goal_id,
+                    start: start.timestamp(),
+                },
+            );
+            state.prompt_input = single_line_textarea_from_string(suggestion);
+        }
+        PromptAction::BackfillDuration { goal_id, start } => {
+            let secs = parse_duration(input).ok_or_else(|| format!("Invalid duration: {input}"))?;
+            if start + secs as i64 > Local::now().timestamp() {
+                return Err("The session would end in the future".to_string());
+            }
+            let started_at = DateTime::from_timestamp(start, 0)
+                .ok_or_else(|| "Invalid start time".to_string())?;
+            close_prompt(state);
+            let pending = PendingSession {
+                label: goal_name(state, goal_id),
+                goal_id,
+                total: secs,
+                duration_override: None,
+                is_reward: false,
+                started_at,
+                is_checkpoint: false,
+            };
+            complete_session(state, pending);
+        }
+        PromptAction::RateSession {
+            goal_id,
+            start_at,
+            after_quantity,
+        } => {
+            if !input.is_empty() {
+                let rating = input
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|r| (1..=5).contains(r))
+                    .ok_or_else(|| format!("Expected a rating from 1 to 5, got {input:?}"))?;
+                let mut meta = state.session_meta(goal_id, start_at);
+                meta.rating = Some(rating);
+                state.set_session_meta(goal_id, start_at, meta);
+            }
+            close_prompt(state);
+            if after_quantity {
+                open_after_quantity_selector(state);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn goal_name(state: &AppState, goal_id: u64) -> String {
+    state
+        .goals
+        .iter()
+        .find(|g| g.id == goal_id)
+        .map(|g| g.name.clone())
+        .unwrap_or_default()
+}
+
 pub fn handle_quantity_done_key(state: &mut AppState, key: &AppKeyEvent) {
     if !matches!(state.mode, Mode::QuantityDoneInput { .. }) {
         return;
@@ -280,16 +939,20 @@ pub fn handle_quantity_done_key(state: &mut AppState, key: &AppKeyEvent) {
         AppKeyCode::Esc => {
             clear_single_line_textarea(&mut state.quantity_input);
             if let Some(pending) = state.pending_session.take() {
+                let is_reward = pending.is_reward;
                 finalize_session(state, pending, None);
+                continue_after_quantity(state, is_reward);
             } else {
                 state.mode = Mode::View;
             }
         }
         AppKeyCode::Enter => {
             let quantity_value = single_line_textarea_value(&state.quantity_input);
-            let qty = parse_optional_u32(&quantity_value);
             if let Some(pending) = state.pending_session.take() {
+                let qty = parse_quantity(state, pending.goal_id, &quantity_value);
+                let is_reward = pending.is_reward;
                 finalize_session(state, pending, qty);
+                continue_after_quantity(state, is_reward);
             }
             clear_single_line_textarea(&mut state.quantity_input);
         }
@@ -297,16 +960,311 @@ pub fn handle_quantity_done_key(state: &mut AppState, key: &AppKeyEvent) {
     }
 }
 
+/// Open the selector chosen by `after_quantity` once a goal session is
+/// logged. The summary moves to the status line so it isn't lost.
+fn continue_after_quantity(state: &mut AppState, is_reward: bool) {
+    let next = match state.config.after_quantity {
+        AfterQuantity::View => return,
+        _ if is_reward => return,
+        AfterQuantity::Reward => Mode::AddReward,
+        AfterQuantity::Session => Mode::AddSession,
+    };
+    // Let the rating prompt finish first; it opens the selector on close.
+    if let Mode::Prompt {
+        action: PromptAction::RateSession { after_quantity, .. },
+        ..
+    } = &mut state.mode
+    {
+        *after_quantity = true;
+        return;
+    }
+    // Nothing was logged if saving the session failed.
+    let Mode::SessionSummary { message, .. } = &state.mode else {
+        return;
+    };
+    state.status = Some(message.clone());
+    open_goal_selector(state, next);
+}
+
+fn open_after_quantity_selector(state: &mut AppState) {
+    match state.config.after_quantity {
+        AfterQuantity::View => {}
+        AfterQuantity::Reward => open_goal_selector(state, Mode::AddReward),
+        AfterQuantity::Session => open_goal_selector(state, Mode::AddSession),
+    }
+}
+
+/// List every session of the selected goal, newest first.
+fn open_goal_history(state: &mut AppState) {
+    let Some(goal_id) = selected_goal_id(state) else {
+        return;
+    };
+    let mut sessions: Vec<HistoryEntry> =
+        successlib::list_sessions_between_dates(state.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.goal_id == goal_id)
+            .map(|s| HistoryEntry {
+                start_at: s.start_at,
+                end_at: s.end_at,
+                quantity: s.quantity,
+            })
+            .collect();
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.start_at));
+    state.mode = Mode::GoalHistory {
+        goal_id,
+        goal_name: goal_name(state, goal_id),
+        sessions,
+        selected: 0,
+    };
+}
+
+pub fn handle_goal_history_key(state: &mut AppState, key: &AppKeyEvent) {
+    let Mode::GoalHistory {
+        goal_id,
+        sessions,
+        selected,
+        ..
+    } = &mut state.mode
+    else {
+        return;
+    };
+    match key.code {
+        AppKeyCode::Up | AppKeyCode::Char('k') => *selected = selected.saturating_sub(1),
+        AppKeyCode::Down | AppKeyCode::Char('j') => {
+            *selected = (*selected + 1).min(sessions.len().saturating_sub(1));
+        }
+        AppKeyCode::Enter => {
+            let Some(session) = sessions.get(*selected) else {
+                return;
+            };
+            let (goal_id, start_at) = (*goal_id, session.start_at);
+            let day = DateTime::from_timestamp(start_at, 0)
+                .map(|dt| dt.with_timezone(&Local).date_naive());
+            close_goal_history(state);
+            if state.kiosk {
+                return;
+            }
+            let Some(day) = day else {
+                return;
+            };
+            if day != state.current_day && state.timer.is_some() && state.config.lock_during_timer {
+                state.status = Some("Day navigation is locked while the timer runs".to_string());
+                return;
+            }
+            go_to_day(state, day);
+            select_session(state, goal_id, start_at);
+        }
+        AppKeyCode::Esc | AppKeyCode::Char('H') => close_goal_history(state),
+        _ => {}
+    }
+}
+
+fn close_goal_history(state: &mut AppState) {
+    state.mode = if state.timer.is_some() {
+        Mode::Timer
+    } else {
+        Mode::View
+    };
+}
+
+/// Move the selection to the viewed day's session of `goal_id` starting at
+/// `start_at`, if it is listed.
+fn select_session(state: &mut AppState, goal_id: u64, start_at: i64) {
+    let items = build_view_items(state, 20);
+    let found = items.iter().position(|item| match item.kind {
+        ViewItemKind::Existing(_, idx) => state
+            .nodes
+            .get(idx)
+            .is_some_and(|n| n.goal_id == goal_id && n.start_at == start_at),
+        _ => false,
+    });
+    if let Some(idx) = found {
+        state.selected = idx;
+        refresh_notes_for_selection(state);
+    }
+}
+
+pub fn handle_settings_key(state: &mut AppState, key: &AppKeyEvent) {
+    let Mode::Settings { selected, editing } = state.mode else {
+        return;
+    };
+    let settings = state.settings();
+    let Some((name, value)) = settings.get(selected.min(settings.len().saturating_sub(1))) else {
+        state.mode = Mode::View;
+        return;
+    };
+    if editing {
+        if handle_single_line_textarea_key(&mut state.prompt_input, key) {
+            return;
+        }
+        match key.code {
+            AppKeyCode::Enter => {
+                let input = single_line_textarea_value(&state.prompt_input);
+                let notes_per_day = state.config.notes_per_day;
+                let result = if selected < state.config.settings().len() {
+                    state
+                        .config
+                        .with_setting(name, &input)
+                        .map(|config| state.config = config)
+                } else {
+                    let mut cli_settings = state.cli_settings.clone();
+                    set_setting(&mut cli_settings, name, &input);
+                    state
+                        .check_cli_settings
+                        .map_or(Ok(()), |check| check(&cli_settings))
+                        .map(|()| state.cli_settings = cli_settings)
+                        .map_err(|e| format!("Invalid {name}: {e}"))
+                };
+                match result {
+                    Ok(()) => {
+                        if state.config.notes_per_day != notes_per_day {
+                            // Same as at startup: split existing notes into
+                            // per-day files, then show them from there.
+                            migrate_to_per_day(state);
+                            refresh_notes_for_selection(state);
+                            refresh_note_snippets(state);
+                        }
+                        state.config_changed = true;
+                        state.status = Some(format!("Set {name}"));
+                        state.mode = Mode::Settings {
+                            selected,
+                            editing: false,
+                        };
+                    }
+                    Err(message) => state.status = Some(message),
+                }
+            }
+            AppKeyCode::Esc => {
+                state.mode = Mode::Settings {
+                    selected,
+                    editing: false,
+                };
+            }
+            _ => {}
+        }
+        return;
+    }
+    match key.code {
+        AppKeyCode::Up | AppKeyCode::Char('k') => {
+            state.mode = Mode::Settings {
+                selected: selected.saturating_sub(1),
+                editing: false,
+            };
+        }
+        AppKeyCode::Down | AppKeyCode::Char('j') => {
+            state.mode = Mode::Settings {
+                selected: (selected + 1).min(settings.len() - 1),
+                editing: false,
+            };
+        }
+        AppKeyCode::Enter => {
+            state.prompt_input = single_line_textarea_from_string(value.clone());
+            state.mode = Mode::Settings {
+                selected,
+                editing: true,
+            };
+        }
+        AppKeyCode::Esc | AppKeyCode::Char(',') => {
+            state.mode = if state.timer.is_some() {
+                Mode::Timer
+            } else {
+                Mode::View
+            };
+        }
+        _ => {}
+    }
+}
+
+/// Show the insights popup for the viewed day.
+pub fn open_insights(state: &mut AppState) {
+    state.insights = day_insights(state);
+    state.mode = Mode::Insights;
+}
+
+pub fn handle_insights_key(state: &mut AppState, key: &AppKeyEvent) {
+    if matches!(
+        key.code,
+        AppKeyCode::Esc | AppKeyCode::Enter | AppKeyCode::Char('i')
+    ) {
+        state.mode = if state.timer.is_some() {
+            Mode::Timer
+        } else {
+            Mode::View
+        };
+    }
+}
+
+pub fn handle_goal_paths_key(state: &mut AppState, key: &AppKeyEvent) {
+    if matches!(
+        key.code,
+        AppKeyCode::Esc | AppKeyCode::Enter | AppKeyCode::Char('P')
+    ) {
+        state.mode = if state.timer.is_some() {
+            Mode::Timer
+        } else {
+            Mode::View
+        };
+    }
+}
+
+pub fn handle_summary_key(state: &mut AppState, _key: &AppKeyEvent) {
+    state.mode = Mode::View;
+}
+
 pub fn handle_timer_key(state: &mut AppState, key: &AppKeyEvent) {
+    if state.config.lock_during_timer && is_day_navigation_key(key) {
+        state.status = Some("Day navigation is locked while the timer runs".to_string());
+        return;
+    }
+    if key.code == AppKeyCode::Char('S') {
+        stop_timer_early(state);
+        return;
+    }
+    if key.code == AppKeyCode::Char('Q') {
+        toggle_quantity_prompt(state);
+        return;
+    }
+    if state.focus_view {
+        // Nothing else is on screen, so only leaving the view does anything.
+        if matches!(key.code, AppKeyCode::Char('f') | AppKeyCode::Esc) {
+            state.focus_view = false;
+        }
+        return;
+    }
+    if key.code == AppKeyCode::Char('f') {
+        state.focus_view = true;
+        return;
+    }
     handle_view_key(state, key);
 }
 
+fn leave_notes_edit(state: &mut AppState) {
+    save_notes_for_selection(state);
+    if state.day_notes {
+        refresh_notes_for_selection(state);
+    }
+    state.mode = if state.timer.is_some() {
+        Mode::Timer
+    } else {
+        Mode::View
+    };
+    state.focused_block = FocusedBlock::SessionsList;
+}
+
+/// Whether the goal whose notes are being edited has `single_line_notes`.
+fn single_line_notes(state: &AppState) -> bool {
+    !state.day_notes
+        && selected_goal_id(state).is_some_and(|id| state.goal_meta(id).single_line_notes)
+}
+
 pub fn handle_notes_key(state: &mut AppState, key: &AppKeyEvent) {
     match key.code {
-        AppKeyCode::Esc => {
+        AppKeyCode::Esc => leave_notes_edit(state),
+        AppKeyCode::Enter if single_line_notes(state) => leave_notes_edit(state),
+        AppKeyCode::Char('s') if key.ctrl => {
             save_notes_for_selection(state);
-            state.mode = Mode::View;
-            state.focused_block = FocusedBlock::SessionsList;
+            state.status = Some("Notes saved".to_string());
         }
         _ => {
             let Some(input) = app_key_to_textarea_input(key, true) else {
@@ -323,6 +1281,7 @@ pub fn handle_notes_key(state: &mut AppState, key: &AppKeyEvent) {
                     | AppKeyCode::Enter
                     | AppKeyCode::Tab
             ) {
+                state.notes_dirty = true;
                 save_notes_for_selection(state);
             }
         }
@@ -334,7 +1293,7 @@ pub fn search_results(state: &AppState) -> Vec<(String, SearchResult)> {
     let q = query.trim();
     let is_reward = matches!(state.mode, Mode::AddReward);
 
-    let goals = successlib::search_goals(
+    let mut goals = successlib::search_goals(
         state.archive_path.clone(),
         q.to_string(),
         Some(is_reward),
@@ -342,17 +1301,70 @@ pub fn search_results(state: &AppState) -> Vec<(String, SearchResult)> {
         Some(true),
     )
     .unwrap_or_default();
+    if state.search_sort_by_total {
+        goals.sort_by_key(|g| std::cmp::Reverse(state.goal_totals.get(&g.id).copied()));
+    }
+    if q.is_empty() {
+        // Stable sort, so pinned goals keep their relative order.
+        goals.sort_by_key(|g| !state.goal_meta(g.id).pinned);
+    } else if q.chars().count() >= 2 && !q.contains(char::is_whitespace) {
+        // search_goals doesn't know acronyms, so look through every goal and
+        // list those whose initials match first.
+        let acronyms: Vec<_> = successlib::search_goals(
+            state.archive_path.clone(),
+            String::new(),
+            Some(is_reward),
+            None,
+            Some(true),
+        )
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|g| matches_acronym(&g.name, q))
+        .collect();
+        goals.retain(|g| !acronyms.iter().any(|a| a.id == g.id));
+        goals.splice(0..0, acronyms);
+    }
 
+    let unmet = if is_reward {
+        HashMap::new()
+    } else {
+        unmet_daily_targets(state)
+    };
+    // Left empty for rewards when the selector opened.
+    let weekly = &state.weekly_progress;
     let mut results: Vec<(String, SearchResult)> = goals
         .into_iter()
         .map(|g| {
+            let pin = if state.goal_meta(g.id).pinned {
+                "★ "
+            } else {
+                ""
+            };
+            let target = unmet
+                .get(&g.id)
+                // Round up so a few seconds short doesn't read "0m left".
+                .map(|&remaining| format_secs_short((remaining + 59) / 60 * 60))
+                .map(|left| format!(" ▲ {left} left today"))
+                .unwrap_or_default();
+            let week = weekly
+                .get(&g.id)
+                .map(|(progress, met)| {
+                    let mark = if *met { "✓" } else { "◆" };
+                    format!(" {mark} {progress} this week")
+                })
+                .unwrap_or_default();
             (
-                format!("{} (id {})", g.name, g.id),
+                format!("{pin}{} (id {}){target}{week}", g.name, g.id),
                 SearchResult::Existing(g),
             )
         })
         .collect();
 
+    // Checkpoints and backfills are for goals that already exist.
+    if matches!(state.mode, Mode::AddCheckpoint | Mode::AddBackfill) {
+        return results;
+    }
+
     let create_label = format!("Create: {q}");
     results.push((
         create_label,
@@ -372,3 +1384,71 @@ pub fn search_results(state: &AppState) -> Vec<(String, SearchResult)> {
 
     results
 }
+
+/// Whether `query` starts the initials of `name`'s words, ignoring case, so
+/// "lr" matches "Learn Rust".
+fn matches_acronym(name: &str, query: &str) -> bool {
+    let initials: String = name
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_lowercase)
+        .collect();
+    initials.starts_with(&query.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn past_limit_leaves_the_day_before_the_first_session_reachable() {
+        let archive = crate::test_support::temp_archive("past-limit");
+        let today = Local::now().date_naive();
+        let mut state = AppState::from_parts(archive.clone(), vec![], vec![], today);
+        state.config.limit_past_navigation = true;
+
+        let long_ago = today - ChronoDuration::days(30);
+        go_to_day(&mut state, long_ago);
+        assert_eq!(state.current_day, long_ago, "no sessions means no limit");
+
+        state.earliest_day = Some(today);
+        go_to_day(&mut state, today - ChronoDuration::days(2));
+        assert_eq!(state.current_day, long_ago);
+        go_to_day(&mut state, today - ChronoDuration::days(1));
+        assert_eq!(state.current_day, today - ChronoDuration::days(1));
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn acronyms_match_initials() {
+        assert!(matches_acronym("Learn Rust", "lr"));
+        assert!(matches_acronym("Clean the house", "cth"));
+        assert!(matches_acronym("Clean the house", "CT"));
+    }
+
+    #[test]
+    fn acronyms_need_initials_in_order() {
+        assert!(!matches_acronym("Learn Rust", "rl"));
+        assert!(!matches_acronym("Learn Rust", "lrx"));
+        assert!(!matches_acronym("Learn", "le"));
+    }
+
+    #[test]
+    fn typical_minutes_ignores_one_odd_session() {
+        let minutes = [5, 25, 50, 25, 30];
+        assert_eq!(
+            typical_minutes(&minutes, DurationSuggestion::Latest),
+            Some(5)
+        );
+        assert_eq!(
+            typical_minutes(&minutes, DurationSuggestion::Median),
+            Some(25)
+        );
+        assert_eq!(
+            typical_minutes(&minutes, DurationSuggestion::Common),
+            Some(25)
+        );
+        assert_eq!(typical_minutes(&[], DurationSuggestion::Median), None);
+    }
+}
@@ -1,10 +1,15 @@
 pub mod app;
+pub mod config;
+pub mod goal_meta;
 pub mod handlers;
 pub mod key_event;
 pub mod notes;
+pub mod session_meta;
 // Re-export success-lib domain types to avoid duplicates.
 pub use successlib::{Goal, Session, SessionKind, SessionView};
 pub mod style;
+#[cfg(test)]
+mod test_support;
 pub mod timer;
 pub mod types;
 pub mod ui;
@@ -1,4 +1,11 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
 use crate::app::AppState;
+use crate::ui::{build_view_items, ViewItemKind};
 use crate::utils::selected_goal_id;
 use tui_textarea::{CursorMove, TextArea};
 
@@ -10,20 +17,400 @@ fn notes_to_textarea(notes: &str) -> TextArea<'static> {
     textarea
 }
 
+// ── Storage ──────────────────────────────────────────────────────────────
+
+fn per_day_notes(state: &AppState) -> bool {
+    // The web build has no filesystem, so it always uses success-lib's notes.
+    cfg!(not(feature = "web")) && state.config.notes_per_day
+}
+
+/// Path of a goal's notes file: `notes/goal_<id>.md`, or
+/// `notes/goal_<id>/YYYY-MM-DD.md` when notes are split by day.
+pub fn note_path(state: &AppState, goal_id: u64, day: NaiveDate) -> PathBuf {
+    let notes_dir = Path::new(&state.archive_path).join("notes");
+    if per_day_notes(state) {
+        notes_dir
+            .join(format!("goal_{goal_id}"))
+            .join(format!("{}.md", day.format("%Y-%m-%d")))
+    } else {
+        notes_dir.join(format!("goal_{goal_id}.md"))
+    }
+}
+
+/// Read a goal's notes for `day` (the day only matters when split by day).
+pub fn load_note(state: &AppState, goal_id: u64, day: NaiveDate) -> String {
+    if per_day_notes(state) {
+        fs::read_to_string(note_path(state, goal_id, day)).unwrap_or_default()
+    } else {
+        successlib::get_note(state.archive_path.clone(), goal_id).unwrap_or_default()
+    }
+}
+
+/// Write the notes of `goal_id`. The error is a message for the status line.
+pub fn store_note(
+    state: &AppState,
+    goal_id: u64,
+    day: NaiveDate,
+    content: String,
+) -> Result<(), String> {
+    if per_day_notes(state) {
+        let path = note_path(state, goal_id, day);
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        fs::write(path, content).map_err(|e| format!("Failed to save notes: {e}"))
+    } else {
+        successlib::edit_note(state.archive_path.clone(), goal_id, content)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to save notes: {e:?}"))
+    }
+}
+
+/// Path of the journal for `day`, not tied to any goal:
+/// `notes/days/YYYY-MM-DD.md`.
+fn day_note_path(state: &AppState, day: NaiveDate) -> PathBuf {
+    Path::new(&state.archive_path)
+        .join("notes")
+        .join("days")
+        .join(format!("{}.md", day.format("%Y-%m-%d")))
+}
+
+pub fn load_day_note(state: &AppState, day: NaiveDate) -> String {
+    fs::read_to_string(day_note_path(state, day)).unwrap_or_default()
+}
+
+pub fn store_day_note(state: &AppState, day: NaiveDate, content: String) -> Result<(), String> {
+    let path = day_note_path(state, day);
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to save the journal: {e}"))
+}
+
+/// When the file shown in the notes panel was last written, if it exists.
+/// The web build keeps notes in memory and has no such time.
+pub fn notes_modified(state: &AppState) -> Option<std::time::SystemTime> {
+    if cfg!(feature = "web") {
+        return None;
+    }
+    let path = if state.day_notes {
+        day_note_path(state, state.current_day)
+    } else {
+        let (goal_id, day) = notes_target(state)?;
+        note_path(state, goal_id, day)
+    };
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Show the viewed day's journal in the notes panel.
+pub fn show_day_notes(state: &mut AppState) {
+    let note = load_day_note(state, state.current_day);
+    state.notes_textarea = notes_to_textarea(&note);
+    state.notes_scroll = 0;
+    state.notes_dirty = false;
+    state.day_notes = true;
+}
+
+/// Split each goal's single notes file into per-day files on its `---`
+/// session headers; notes without any header go to today's file. Goals that
+/// already have a per-day folder are skipped and the original files are left
+/// in place.
+pub fn migrate_to_per_day(state: &AppState) {
+    if !per_day_notes(state) {
+        return;
+    }
+    let notes_dir = Path::new(&state.archive_path).join("notes");
+    let today = Local::now().date_naive();
+    for goal in &state.goals {
+        let goal_dir = notes_dir.join(format!("goal_{}", goal.id));
+        if goal_dir.exists() {
+            continue;
+        }
+        let note = successlib::get_note(state.archive_path.clone(), goal.id).unwrap_or_default();
+        for (day, section) in split_note_by_day(&note, today) {
+            let path = goal_dir.join(format!("{}.md", day.format("%Y-%m-%d")));
+            let _ = fs::create_dir_all(&goal_dir);
+            let mut content = fs::read_to_string(&path).unwrap_or_default();
+            content.push_str(&section);
+            let _ = fs::write(path, content);
+        }
+    }
+}
+
+/// Group a note's sections by the day in their `---\nYYYY-MM-DD HH:MM` header.
+/// Text before the first header stays with the first day; a note without
+/// headers goes to `fallback` whole.
+fn split_note_by_day(note: &str, fallback: NaiveDate) -> Vec<(NaiveDate, String)> {
+    let lines: Vec<&str> = note.split('\n').collect();
+    let mut sections: Vec<(Option<NaiveDate>, String)> = vec![(None, String::new())];
+    for (i, line) in lines.iter().enumerate() {
+        let header_day = (line.trim() == "---")
+            .then(|| lines.get(i + 1))
+            .flatten()
+            .and_then(|l| NaiveDateTime::parse_from_str(l.trim(), "%Y-%m-%d %H:%M").ok())
+            .map(|dt| dt.date());
+        if let Some(day) = header_day {
+            sections.push((Some(day), String::new()));
+        }
+        let current = &mut sections.last_mut().expect("sections is never empty").1;
+        current.push_str(line);
+        if i + 1 < lines.len() {
+            current.push('\n');
+        }
+    }
+
+    let preamble = sections.remove(0).1;
+    let mut by_day: Vec<(NaiveDate, String)> = Vec::new();
+    for (day, text) in sections {
+        let Some(day) = day else { continue };
+        match by_day.iter_mut().find(|(d, _)| *d == day) {
+            Some((_, existing)) => existing.push_str(&text),
+            None => by_day.push((day, text)),
+        }
+    }
+    match by_day.first_mut() {
+        Some((_, first)) => first.insert_str(0, &preamble),
+        None if !preamble.trim().is_empty() => by_day.push((fallback, preamble)),
+        None => {}
+    }
+    by_day
+}
+
+// ── Selection ────────────────────────────────────────────────────────────
+
+/// Goal and day of the notes in the notes panel: the locked ones, or the
+/// selected goal's on the viewed day.
+pub fn notes_target(state: &AppState) -> Option<(u64, NaiveDate)> {
+    state
+        .notes_lock
+        .or_else(|| selected_goal_id(state).map(|goal_id| (goal_id, state.current_day)))
+}
+
 pub fn refresh_notes_for_selection(state: &mut AppState) {
-    if let Some(goal_id) = selected_goal_id(state) {
-        let notes = successlib::get_note(state.archive_path.clone(), goal_id).unwrap_or_default();
+    state.day_notes = false;
+    state.notes_dirty = false;
+    if let Some((goal_id, day)) = notes_target(state) {
+        let notes = load_note(state, goal_id, day);
         state.notes_textarea = notes_to_textarea(&notes);
     } else {
         state.notes_textarea = TextArea::default();
         state.notes_textarea.set_tab_length(4);
     }
+    // Keep the reader's place in locked notes.
+    if state.notes_lock.is_some() {
+        return;
+    }
+    state.notes_scroll = selected_session_start(state)
+        .and_then(|start_at| session_header_line(state.notes_textarea.lines(), start_at))
+        .unwrap_or(0) as u16;
+}
+
+fn selected_session_start(state: &AppState) -> Option<i64> {
+    let items = build_view_items(state, 20);
+    match items.get(state.selected)?.kind {
+        ViewItemKind::Existing(_, idx) => state.nodes.get(idx).map(|n| n.start_at),
+        _ => None,
+    }
 }
 
-/// Save the notes for the currently selected goal.
+/// Index of the `---` line heading the notes written for the session that
+/// started at `start_at`, as written by `start_timer`.
+pub fn session_header_line(lines: &[String], start_at: i64) -> Option<usize> {
+    let stamp = chrono::DateTime::from_timestamp(start_at, 0)?
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+    let stamp_idx = lines.iter().position(|line| line.trim() == stamp)?;
+    if stamp_idx > 0 && lines[stamp_idx - 1].trim() == "---" {
+        Some(stamp_idx - 1)
+    } else {
+        Some(stamp_idx)
+    }
+}
+
+/// First non-empty line written under the session header for `start_at`.
+fn session_snippet(note: &str, start_at: i64) -> Option<String> {
+    let lines: Vec<String> = note.split('\n').map(str::to_string).collect();
+    let header = session_header_line(&lines, start_at)?;
+    let body_start = if lines[header].trim() == "---" {
+        header + 2
+    } else {
+        header + 1
+    };
+    lines
+        .iter()
+        .skip(body_start)
+        .map(|line| line.trim())
+        .take_while(|line| *line != "---")
+        .find(|line| !line.is_empty() && *line != NOT_LOGGED_MARKER)
+        .map(str::to_string)
+}
+
+/// Recompute the note snippets shown under the viewed day's sessions.
+pub fn refresh_note_snippets(state: &mut AppState) {
+    let mut notes: HashMap<u64, String> = HashMap::new();
+    let mut snippets = HashMap::new();
+    for n in &state.nodes {
+        let note = notes
+            .entry(n.goal_id)
+            .or_insert_with(|| load_note(state, n.goal_id, state.current_day));
+        if let Some(snippet) = session_snippet(note, n.start_at) {
+            snippets.insert((n.goal_id, n.start_at), snippet);
+        }
+    }
+    state.note_snippets = snippets;
+}
+
+/// Save the notes for the currently selected goal, or the day journal if
+/// that is what the notes panel shows.
 pub fn save_notes_for_selection(state: &mut AppState) {
-    if let Some(goal_id) = selected_goal_id(state) {
+    let saved = if state.day_notes {
+        let content = state.notes_textarea.lines().join("\n");
+        store_day_note(state, state.current_day, content)
+    } else if let Some((goal_id, day)) = notes_target(state) {
         let content = state.notes_textarea.lines().join("\n");
-        let _ = successlib::edit_note(state.archive_path.clone(), goal_id, content);
+        let saved = store_note(state, goal_id, day, content);
+        refresh_note_snippets(state);
+        saved
+    } else {
+        Ok(())
+    };
+    match saved {
+        Ok(()) => state.notes_dirty = false,
+        Err(message) => state.status = Some(message),
+    }
+}
+
+// ── Interrupted sessions ─────────────────────────────────────────────────
+
+/// Written under a session header when the user declines to log it, so the
+/// header isn't offered for recovery again.
+const NOT_LOGGED_MARKER: &str = "(interrupted, not logged)";
+
+/// Days of per-day note files checked for interrupted sessions.
+const RECOVERY_DAYS: i64 = 7;
+
+fn header_stamp(ts: i64) -> Option<String> {
+    Some(
+        DateTime::from_timestamp(ts, 0)?
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+    )
+}
+
+/// Start of the last session header in `note`, unless it was marked as not logged.
+fn last_session_header(note: &str) -> Option<DateTime<Local>> {
+    let lines: Vec<&str> = note.lines().map(str::trim).collect();
+    let idx = lines.windows(2).rposition(|pair| {
+        pair[0] == "---" && NaiveDateTime::parse_from_str(pair[1], "%Y-%m-%d %H:%M").is_ok()
+    })?;
+    if lines.get(idx + 2) == Some(&NOT_LOGGED_MARKER) {
+        return None;
+    }
+    let started = NaiveDateTime::parse_from_str(lines[idx + 1], "%Y-%m-%d %H:%M").ok()?;
+    Local.from_local_datetime(&started).earliest()
+}
+
+/// The most recent session header, across all goals, without a recorded
+/// session. `start_timer` writes the header before the session is recorded,
+/// so one is left behind when the app exits while a timer runs.
+pub fn find_interrupted_session(state: &AppState) -> Option<(u64, DateTime<Utc>)> {
+    let recorded: HashSet<(u64, String)> =
+        successlib::list_sessions_between_dates(state.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|s| Some((s.goal_id, header_stamp(s.start_at)?)))
+            .collect();
+    let today = Local::now().date_naive();
+    let days: Vec<NaiveDate> = if per_day_notes(state) {
+        (0..RECOVERY_DAYS)
+            .map(|d| today - chrono::Duration::days(d))
+            .collect()
+    } else {
+        vec![today]
+    };
+
+    state
+        .goals
+        .iter()
+        .flat_map(|goal| {
+            days.iter()
+                .filter_map(|&day| last_session_header(&load_note(state, goal.id, day)))
+                .map(move |started| (goal.id, started))
+        })
+        .filter(|(goal_id, started)| {
+            let stamp = started.format("%Y-%m-%d %H:%M").to_string();
+            !recorded.contains(&(*goal_id, stamp))
+        })
+        .max_by_key(|(_, started)| *started)
+        .map(|(goal_id, started)| (goal_id, started.with_timezone(&Utc)))
+}
+
+/// Mark the header of an interrupted session as deliberately not logged.
+pub fn mark_not_logged(
+    state: &AppState,
+    goal_id: u64,
+    started_at: DateTime<Utc>,
+) -> Result<(), String> {
+    let day = started_at.with_timezone(&Local).date_naive();
+    let note = load_note(state, goal_id, day);
+    let mut lines: Vec<String> = note.split('\n').map(str::to_string).collect();
+    let Some(idx) = session_header_line(&lines, started_at.timestamp()) else {
+        return Ok(());
+    };
+    lines.insert(idx + 2, NOT_LOGGED_MARKER.to_string());
+    store_note(state, goal_id, day, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn note_without_headers_goes_to_the_fallback_day() {
+        let fallback = date("2024-05-15");
+        assert_eq!(
+            split_note_by_day("Imported text\nmore", fallback),
+            vec![(fallback, "Imported text\nmore".to_string())]
+        );
+        assert!(split_note_by_day("  \n", fallback).is_empty());
+    }
+
+    #[test]
+    fn preamble_stays_with_the_first_day() {
+        let note = "Intro\n---\n2024-05-01 09:00\nFirst\n---\n2024-05-02 10:00\nSecond";
+        assert_eq!(
+            split_note_by_day(note, date("2024-05-15")),
+            vec![
+                (
+                    date("2024-05-01"),
+                    "Intro\n---\n2024-05-01 09:00\nFirst\n".to_string()
+                ),
+                (
+                    date("2024-05-02"),
+                    "---\n2024-05-02 10:00\nSecond".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sections_of_the_same_day_are_joined() {
+        let note = "---\n2024-05-01 09:00\nMorning\n---\n2024-05-02 10:00\nNext day\n---\n2024-05-01 18:00\nEvening";
+        let by_day = split_note_by_day(note, date("2024-05-15"));
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(
+            by_day[0],
+            (
+                date("2024-05-01"),
+                "---\n2024-05-01 09:00\nMorning\n---\n2024-05-01 18:00\nEvening".to_string()
+            )
+        );
+        assert_eq!(by_day[1].0, date("2024-05-02"));
     }
 }
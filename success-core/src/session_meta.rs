@@ -0,0 +1,55 @@
+//! Per-session details that success-lib's `Session` doesn't store.
+//!
+//! Kept in `session_meta.json` inside the archive, keyed by goal id and start
+//! time. The web build keeps them in memory only, like the rest of its data.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionMeta {
+    /// Seconds the timer was set to, when the session was stopped early.
+    pub planned_secs: Option<u64>,
+    /// The session was flagged as derailed.
+    pub interrupted: bool,
+    /// How the session went, from 1 to 5.
+    pub rating: Option<u8>,
+}
+
+pub type SessionMetaMap = HashMap<String, SessionMeta>;
+
+/// Key of the session of `goal_id` that started at `start_at`.
+pub fn key(goal_id: u64, start_at: i64) -> String {
+    format!("{goal_id}:{start_at}")
+}
+
+#[cfg(not(feature = "web"))]
+pub fn meta_path(archive_path: &str) -> std::path::PathBuf {
+    std::path::Path::new(archive_path).join("session_meta.json")
+}
+
+#[cfg(not(feature = "web"))]
+pub fn load(archive_path: &str) -> SessionMetaMap {
+    std::fs::read_to_string(meta_path(archive_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(feature = "web")]
+pub fn load(_archive_path: &str) -> SessionMetaMap {
+    SessionMetaMap::new()
+}
+
+#[cfg(not(feature = "web"))]
+pub fn save(archive_path: &str, meta: &SessionMetaMap) -> std::io::Result<()> {
+    let content = serde_json::to_string_pretty(meta).map_err(std::io::Error::other)?;
+    std::fs::write(meta_path(archive_path), content)
+}
+
+#[cfg(feature = "web")]
+pub fn save(_archive_path: &str, _meta: &SessionMetaMap) -> std::io::Result<()> {
+    Ok(())
+}
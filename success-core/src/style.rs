@@ -3,3 +3,13 @@ use ratatui::style::Color;
 pub const BLUE: Color = Color::Rgb(0x89, 0xB4, 0xFA);
 pub const GRAY_DIM: Color = Color::DarkGray;
 pub const YELLOW: Color = Color::Rgb(0xF9, 0xE2, 0x79);
+
+/// Parse a "#RRGGBB" (or "RRGGBB") hex colour.
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
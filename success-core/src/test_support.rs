@@ -0,0 +1,23 @@
+//! Helpers shared by the unit tests.
+
+use chrono::Utc;
+use successlib::Goal;
+
+/// A fresh, empty archive in the temp dir. `test` keeps the name readable.
+pub fn temp_archive(test: &str) -> String {
+    let archive = std::env::temp_dir().join(format!(
+        "success-core-{test}-{}-{}",
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+    std::fs::create_dir_all(&archive).unwrap();
+    archive.to_string_lossy().to_string()
+}
+
+/// A fresh archive holding a single goal named `name`.
+pub fn archive_with_goal(test: &str, name: &str) -> (String, Goal) {
+    let archive = temp_archive(test);
+    let goal = successlib::add_goal(archive.clone(), name.to_string(), false, vec![], None)
+        .expect("Failed to add goal");
+    (archive, goal)
+}
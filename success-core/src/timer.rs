@@ -1,12 +1,31 @@
 use chrono::{Local, Utc};
 
 use crate::app::AppState;
-use crate::notes::{refresh_notes_for_selection, save_notes_for_selection};
+use crate::handlers::load_day;
+use crate::notes::{
+    load_note, refresh_note_snippets, refresh_notes_for_selection, save_notes_for_selection,
+    store_note,
+};
 use crate::types::*;
 use crate::ui::build_view_items;
-use crate::utils::goal_quantity_name;
+use crate::utils::{
+    default_selection, format_quantity_with_unit, format_secs_short, goal_minutes_sparkline,
+    goal_quantity_name,
+};
+
+/// How long the session summary stays up before closing on its own.
+const SUMMARY_SECONDS: i64 = 3;
+
+/// Length of a checkpoint session, which only marks that something was done.
+pub const CHECKPOINT_SECONDS: u64 = 1;
 
 pub fn tick_timer(state: &mut AppState) {
+    if let Mode::SessionSummary { shown_at, .. } = state.mode {
+        if (Utc::now() - shown_at).num_seconds() >= SUMMARY_SECONDS {
+            state.mode = Mode::View;
+        }
+    }
+
     if let Some(timer) = state.timer.as_mut() {
         let now_utc = Utc::now();
         let elapsed_seconds = (now_utc - timer.started_at).num_seconds();
@@ -27,36 +46,128 @@ pub fn tick_timer(state: &mut AppState) {
 }
 
 pub fn finish_timer(state: &mut AppState) {
-    if let Some(timer) = state.timer.take() {
-        if matches!(state.mode, Mode::NotesEdit) {
-            save_notes_for_selection(state);
-        }
+    end_timer(state, None);
+}
 
-        let pending = PendingSession {
-            label: timer.label.clone(),
-            goal_id: timer.goal_id,
-            total: timer.total,
-            is_reward: timer.is_reward,
-            started_at: timer.started_at,
-        };
+/// End the running timer now and record only the time actually spent.
+pub fn stop_timer_early(state: &mut AppState) {
+    let Some(timer) = &state.timer else {
+        return;
+    };
+    let elapsed = (Utc::now() - timer.started_at).num_seconds().max(0) as u64;
+    if elapsed >= timer.total {
+        end_timer(state, None);
+    } else {
+        end_timer(state, Some(elapsed));
+    }
+}
+
+/// Log the time the running timer has run so far, without asking for a
+/// quantity, e.g. when quitting.
+pub fn record_running_timer(state: &mut AppState) {
+    let Some(timer) = state.timer.take() else {
+        return;
+    };
+    let elapsed = (Utc::now() - timer.started_at).num_seconds().max(0) as u64;
+    let pending = pending_from_timer(timer, (elapsed < timer.total).then_some(elapsed));
+    finalize_session(state, pending, None);
+}
 
-        let quantity_name = goal_quantity_name(state, timer.goal_id);
-        let needs_quantity = quantity_name.is_some();
-
-        if needs_quantity {
-            state.pending_session = Some(pending);
-            clear_single_line_textarea(&mut state.quantity_input);
-            state.mode = Mode::QuantityDoneInput {
-                goal_name: timer.label,
-                quantity_name,
-            };
-            state.focused_block = FocusedBlock::SessionsList;
+/// The session to record for `timer`, with idle time taken off.
+fn pending_from_timer(timer: TimerState, duration_override: Option<u64>) -> PendingSession {
+    let duration_override = match timer.idle_secs {
+        0 => duration_override,
+        idle => Some(
+            duration_override
+                .unwrap_or(timer.total)
+                .saturating_sub(idle),
+        ),
+    };
+    PendingSession {
+        label: timer.label,
+        goal_id: timer.goal_id,
+        total: timer.total,
+        duration_override,
+        is_reward: timer.is_reward,
+        started_at: timer.started_at,
+        is_checkpoint: false,
+    }
+}
+
+fn end_timer(state: &mut AppState, duration_override: Option<u64>) {
+    // Save while the timer is still running: the selection may point at the
+    // running timer item, which disappears once the timer is taken.
+    if state.timer.is_some() && matches!(state.mode, Mode::NotesEdit) {
+        save_notes_for_selection(state);
+    }
+    state.focus_view = false;
+    if let Some(timer) = state.timer.take() {
+        let ask_quantity = timer.ask_quantity;
+        let pending = pending_from_timer(timer, duration_override);
+        if ask_quantity {
+            complete_session(state, pending);
         } else {
             finalize_session(state, pending, None);
         }
     }
 }
 
+/// Switch whether the running timer asks for the quantity done when it ends,
+/// overriding the goal's `prompt_quantity` for this session.
+pub fn toggle_quantity_prompt(state: &mut AppState) {
+    let Some(timer) = state.timer.as_ref() else {
+        return;
+    };
+    let Some(quantity_name) = goal_quantity_name(state, timer.goal_id) else {
+        state.status = Some(format!("{} doesn't track a quantity", timer.label));
+        return;
+    };
+    let ask = !timer.ask_quantity;
+    if let Some(timer) = state.timer.as_mut() {
+        timer.ask_quantity = ask;
+    }
+    state.status = Some(if ask {
+        format!("Will ask for {quantity_name} when the timer ends")
+    } else {
+        format!("Won't ask for {quantity_name} this time")
+    });
+}
+
+/// Record `pending`, first asking for the quantity done if its goal tracks one.
+pub fn complete_session(state: &mut AppState, pending: PendingSession) {
+    let quantity_name = goal_quantity_name(state, pending.goal_id);
+    if quantity_name.is_some() {
+        clear_single_line_textarea(&mut state.quantity_input);
+        state.mode = Mode::QuantityDoneInput {
+            goal_name: pending.label.clone(),
+            quantity_name,
+        };
+        state.pending_session = Some(pending);
+        state.focused_block = FocusedBlock::SessionsList;
+    } else {
+        finalize_session(state, pending, None);
+    }
+}
+
+/// Log a checkpoint for `goal_id` right now, without a timer. Goals with a
+/// quantity ask for it first, like a finished session does.
+pub fn log_checkpoint(state: &mut AppState, goal_name: String, goal_id: u64) {
+    let today = Local::now().date_naive();
+    if state.current_day != today {
+        load_day(state, today);
+    }
+    let pending = PendingSession {
+        label: goal_name,
+        goal_id,
+        total: CHECKPOINT_SECONDS,
+        duration_override: None,
+        is_reward: false,
+        started_at: Utc::now(),
+        is_checkpoint: true,
+    };
+    complete_session(state, pending);
+}
+
 pub fn start_timer(
     state: &mut AppState,
     goal_name: String,
@@ -70,24 +181,19 @@ pub fn start_timer(
 
     let today = Local::now().date_naive();
     if state.current_day != today {
-        state.current_day = today;
-        state.nodes = successlib::list_day_sessions(
-            state.archive_path.clone(),
-            today.format("%Y-%m-%d").to_string(),
-        )
-        .unwrap_or_default();
-        state.selected = build_view_items(state, 20).len().saturating_sub(1);
-        refresh_notes_for_selection(state);
+        load_day(state, today);
     }
 
     let started_at = Utc::now();
 
     // Append session start header to notes
-    let mut note = successlib::get_note(state.archive_path.clone(), goal_id).unwrap_or_default();
+    let mut note = load_note(state, goal_id, today);
     let start_local = started_at.with_timezone(&Local);
     let start_stamp = start_local.format("%Y-%m-%d %H:%M");
     note.push_str(&format!("---\n{start_stamp}\n"));
-    let _ = successlib::edit_note(state.archive_path.clone(), goal_id, note);
+    if let Err(message) = store_note(state, goal_id, today, note) {
+        state.status = Some(message);
+    }
 
     state.timer = Some(TimerState {
         label: goal_name,
@@ -96,10 +202,19 @@ pub fn start_timer(
         total: seconds as u64,
         is_reward,
         started_at,
+        skip_commands: std::mem::take(&mut state.skip_commands),
+        idle_secs: 0,
+        ask_quantity: state.goal_meta(goal_id).prompt_quantity,
     });
     state.selected = build_view_items(state, 20).len().saturating_sub(1);
     refresh_notes_for_selection(state);
-    state.mode = Mode::Timer;
+    if state.config.notes_on_start {
+        // The cursor is already at the end, right under the new header.
+        state.mode = Mode::NotesEdit;
+        state.focused_block = FocusedBlock::Notes;
+    } else {
+        state.mode = Mode::Timer;
+    }
 }
 
 pub fn finalize_session(state: &mut AppState, pending: PendingSession, quantity: Option<u32>) {
@@ -109,8 +224,11 @@ pub fn finalize_session(state: &mut AppState, pending: PendingSession, quantity:
 
     state.mode = Mode::View;
     state.focused_block = FocusedBlock::SessionsList;
-    let duration_secs = pending.total.min(u32::MAX as u64) as u32;
-    let created = successlib::add_session(
+    let duration_secs = pending
+        .duration_override
+        .unwrap_or(pending.total)
+        .min(u32::MAX as u64) as u32;
+    let created = match successlib::add_session(
         state.archive_path.clone(),
         pending.goal_id,
         pending.label.clone(),
@@ -118,20 +236,177 @@ pub fn finalize_session(state: &mut AppState, pending: PendingSession, quantity:
         duration_secs,
         pending.is_reward,
         quantity,
-    )
-    .expect("Failed to add session");
+    ) {
+        Ok(created) => created,
+        Err(e) => {
+            // Keep the app alive; the numbers let the session be backfilled.
+            state.status = Some(format!(
+                "Failed to log {}m on {}: {e:?}",
+                duration_secs / 60,
+                pending.label
+            ));
+            return;
+        }
+    };
+    if let Some(actual) = pending.duration_override {
+        if actual != pending.total {
+            let mut meta = state.session_meta(pending.goal_id, created.start_at);
+            meta.planned_secs = Some(pending.total);
+            state.set_session_meta(pending.goal_id, created.start_at, meta);
+        }
+    }
+    state.sparkline = goal_minutes_sparkline(&state.archive_path, state.current_day);
+
+    let qty_label = quantity
+        .map(|q| {
+            format!(
+                " ({})",
+                format_quantity_with_unit(state, pending.goal_id, q)
+            )
+        })
+        .unwrap_or_default();
+    let logged = if duration_secs < 60 {
+        format!("{duration_secs}s")
+    } else {
+        format_secs_short(duration_secs as i64)
+    };
+    let message = if pending.is_checkpoint {
+        format!("Checkpoint for {}{qty_label}", pending.label)
+    } else {
+        format!("Logged {logged} on {}{qty_label}", pending.label)
+    };
+    state.mode = Mode::SessionSummary {
+        message,
+        shown_at: Utc::now(),
+    };
 
     let timer_day = chrono::DateTime::from_timestamp(created.start_at, 0)
         .map(|dt| dt.with_timezone(&Local).date_naive())
         .unwrap_or_else(|| Local::now().date_naive());
+    state.earliest_day = Some(
+        state
+            .earliest_day
+            .map_or(timer_day, |day| day.min(timer_day)),
+    );
     if state.current_day == timer_day {
         state.nodes = successlib::list_day_sessions(
             state.archive_path.clone(),
             timer_day.format("%Y-%m-%d").to_string(),
         )
         .unwrap_or_default();
-        let items = build_view_items(state, 20);
-        state.selected = items.len().saturating_sub(1);
+        state.selected = default_selection(state);
         refresh_notes_for_selection(state);
+        refresh_note_snippets(state);
+    }
+
+    if state.config.rate_sessions && !pending.is_reward && !pending.is_checkpoint {
+        if let Mode::SessionSummary { message, .. } = &state.mode {
+            state.status = Some(message.clone());
+        }
+        clear_single_line_textarea(&mut state.prompt_input);
+        state.mode = Mode::Prompt {
+            title: format!("Rate {} from 1 to 5 (blank to skip)", pending.label),
+            action: PromptAction::RateSession {
+                goal_id: pending.goal_id,
+                start_at: created.start_at,
+                after_quantity: false,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::archive_with_goal;
+    use tui_textarea::{Input, Key};
+
+    fn recorded_secs(archive: &str, goal_id: u64) -> Vec<i64> {
+        successlib::list_sessions_between_dates(archive.to_string(), None, None)
+            .unwrap_or_default()
+            .iter()
+            .filter(|s| s.goal_id == goal_id)
+            .map(|s| s.end_at - s.start_at)
+            .collect()
+    }
+
+    #[test]
+    fn finishing_timer_keeps_notes_being_edited() {
+        let (archive, goal) = archive_with_goal("finish-timer", "Write");
+        let mut state = AppState::new(archive.clone());
+        start_timer(&mut state, goal.name.clone(), goal.id, 60, false);
+
+        state.mode = Mode::NotesEdit;
+        for c in "unsaved thought".chars() {
+            state.notes_textarea.input(Input {
+                key: Key::Char(c),
+                ctrl: false,
+                alt: false,
+                shift: false,
+            });
+        }
+        finish_timer(&mut state);
+
+        let today = Local::now().date_naive();
+        assert!(load_note(&state, goal.id, today).contains("unsaved thought"));
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn focus_view_ends_with_the_timer() {
+        let (archive, goal) = archive_with_goal("focus-view", "Write");
+        let mut state = AppState::new(archive.clone());
+        start_timer(&mut state, goal.name.clone(), goal.id, 60, false);
+        let key = |code| AppKeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+
+        state.handle_key(key(AppKeyCode::Char('f')));
+        assert!(state.focus_view);
+        state.handle_key(key(AppKeyCode::Char('e')));
+        assert!(matches!(state.mode, Mode::Timer));
+        finish_timer(&mut state);
+        assert!(!state.focus_view);
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn natural_finish_records_full_duration() {
+        let (archive, goal) = archive_with_goal("natural-finish", "Write");
+        let mut state = AppState::new(archive.clone());
+        start_timer(&mut state, goal.name.clone(), goal.id, 600, false);
+        if let Some(timer) = state.timer.as_mut() {
+            timer.started_at -= chrono::Duration::seconds(700);
+        }
+        tick_timer(&mut state);
+
+        assert!(state.timer.is_none());
+        assert_eq!(recorded_secs(&archive, goal.id), vec![600]);
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn early_stop_records_elapsed_time() {
+        let (archive, goal) = archive_with_goal("early-stop", "Write");
+        let mut state = AppState::new(archive.clone());
+        start_timer(&mut state, goal.name.clone(), goal.id, 600, false);
+        if let Some(timer) = state.timer.as_mut() {
+            timer.started_at -= chrono::Duration::seconds(120);
+        }
+        stop_timer_early(&mut state);
+
+        assert!(state.timer.is_none());
+        let recorded = recorded_secs(&archive, goal.id);
+        assert_eq!(recorded.len(), 1);
+        // Allow for the clock ticking over while the test runs.
+        assert!((120..=121).contains(&recorded[0]));
+
+        let _ = std::fs::remove_dir_all(&archive);
     }
 }
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use tui_textarea::{CursorMove, Input, Key, TextArea};
 
 use crate::key_event::{AppKeyCode, AppKeyEvent};
@@ -85,11 +86,55 @@ pub fn app_key_to_textarea_input(key: &AppKeyEvent, multiline: bool) -> Option<I
 
 // ── Enums ────────────────────────────────────────────────────────────────
 
+/// What a `Mode::Prompt` does with the entered line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    /// Jump to the entered day.
+    GoToDay,
+    /// Append the entered line to the goal's notes for the viewed day.
+    AppendNote { goal_id: u64 },
+    /// Start time (HH:MM on the viewed day) of a session being backfilled.
+    BackfillStart { goal_id: u64 },
+    /// Duration of a backfilled session starting at `start`.
+    BackfillDuration { goal_id: u64, start: i64 },
+    /// Rating (1-5) of the session just logged. `after_quantity` opens the
+    /// `after_quantity` selector once the rating is done.
+    RateSession {
+        goal_id: u64,
+        start_at: i64,
+        after_quantity: bool,
+    },
+}
+
+impl PromptAction {
+    pub fn help(self) -> &'static str {
+        match self {
+            PromptAction::GoToDay => "Enter: go • Esc: cancel",
+            PromptAction::AppendNote { .. } => "Enter: append • Esc: cancel",
+            PromptAction::BackfillStart { .. } => "Enter: next • Esc: cancel",
+            PromptAction::BackfillDuration { .. } => "Enter: log • Esc: cancel",
+            PromptAction::RateSession { .. } => "Enter: rate • Esc: skip",
+        }
+    }
+}
+
+/// One session in the goal history list.
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryEntry {
+    pub start_at: i64,
+    pub end_at: i64,
+    pub quantity: Option<u32>,
+}
+
 #[derive(Debug)]
 pub enum Mode {
     View,
     AddSession,
     AddReward,
+    /// Goal selector that logs a checkpoint instead of starting a timer.
+    AddCheckpoint,
+    /// Goal selector for logging a past session on the viewed day.
+    AddBackfill,
     GoalForm,
     QuantityDoneInput {
         goal_name: String,
@@ -102,26 +147,195 @@ pub enum Mode {
     },
     Timer,
     NotesEdit,
+    /// End-of-day summary of the viewed day.
+    Insights,
+    /// Editable config values; `editing` when the selected one is being
+    /// changed in `prompt_input`.
+    Settings {
+        selected: usize,
+        editing: bool,
+    },
+    /// Every session of one goal across all days, newest first.
+    GoalHistory {
+        goal_id: u64,
+        goal_name: String,
+        sessions: Vec<HistoryEntry>,
+        selected: usize,
+    },
+    /// Files in the archive that belong to a goal, as (label, path) pairs.
+    GoalPaths {
+        goal_name: String,
+        paths: Vec<(String, String)>,
+    },
+    /// One-line input whose value is handled according to `action`.
+    Prompt {
+        title: String,
+        action: PromptAction,
+    },
+    /// Brief confirmation of a just-recorded session.
+    SessionSummary {
+        message: String,
+        shown_at: DateTime<Utc>,
+    },
+    /// "Start reward now?" prompt shown before the reward duration dialog.
+    ConfirmReward {
+        goal_name: String,
+        goal_id: u64,
+    },
+    /// Prompt for the duration of a session that was started but never
+    /// recorded, e.g. because the app crashed while its timer ran.
+    RecoverSession {
+        goal_name: String,
+        goal_id: u64,
+        is_reward: bool,
+        started_at: DateTime<Utc>,
+    },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FormField {
     #[default]
     GoalName,
     Quantity,
+    AllowNegative,
+    DefaultDuration,
+    DailyTarget,
+    WeeklyTarget,
+    RewardWeight,
     Commands,
 }
 
+impl FormField {
+    /// Fields shown in the goal form, in tab order.
+    /// The web build has no commands field since commands never run there.
+    #[cfg(not(feature = "web"))]
+    pub const ORDER: &'static [FormField] = &[
+        FormField::GoalName,
+        FormField::Quantity,
+        FormField::AllowNegative,
+        FormField::DefaultDuration,
+        FormField::DailyTarget,
+        FormField::WeeklyTarget,
+        FormField::RewardWeight,
+        FormField::Commands,
+    ];
+    #[cfg(feature = "web")]
+    pub const ORDER: &'static [FormField] = &[
+        FormField::GoalName,
+        FormField::Quantity,
+        FormField::AllowNegative,
+        FormField::DefaultDuration,
+        FormField::DailyTarget,
+        FormField::WeeklyTarget,
+        FormField::RewardWeight,
+    ];
+
+    /// Prompt shown before the field's input in the goal form.
+    pub fn label(self) -> &'static str {
+        match self {
+            FormField::GoalName => "Name: ",
+            FormField::Quantity => "Quantity name (optional): ",
+            FormField::AllowNegative => "Allow negative quantities (y/N): ",
+            FormField::DefaultDuration => "Default duration (optional, e.g. 45m): ",
+            FormField::DailyTarget => "Daily target (optional, e.g. 1h): ",
+            FormField::WeeklyTarget => "Weekly target (optional, e.g. 3h or 5x for 5 sessions): ",
+            FormField::RewardWeight => "Weight (optional, balance cost per minute, e.g. 1.5): ",
+            FormField::Commands => "Commands (optional, separated by ;): ",
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct FormState {
     pub current_field: FormField,
     pub goal_name: TextArea<'static>,
     pub quantity_name: TextArea<'static>,
+    pub allow_negative: TextArea<'static>,
+    pub default_duration: TextArea<'static>,
+    pub daily_target: TextArea<'static>,
+    pub weekly_target: TextArea<'static>,
+    pub reward_weight: TextArea<'static>,
     pub commands: TextArea<'static>,
     pub is_reward: bool,
+    /// Fields to show, in tab order (the `goal_form_fields` option). Empty
+    /// shows all of them.
+    pub order: Vec<FormField>,
+    /// The user was warned about dangerous-looking commands; the next Enter
+    /// saves anyway.
+    pub danger_confirmed: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+impl FormState {
+    /// Fields shown for this form, in tab order. The name always comes
+    /// first. Only rewards have a weight and only goals can have negative
+    /// quantities.
+    pub fn fields(&self) -> Vec<FormField> {
+        let order = if self.order.is_empty() {
+            FormField::ORDER
+        } else {
+            &self.order
+        };
+        let mut fields = vec![FormField::GoalName];
+        for &field in order {
+            let applies = match field {
+                FormField::RewardWeight => self.is_reward,
+                FormField::AllowNegative => !self.is_reward,
+                _ => true,
+            };
+            if applies && FormField::ORDER.contains(&field) && !fields.contains(&field) {
+                fields.push(field);
+            }
+        }
+        fields
+    }
+
+    pub fn focus_next(&mut self) {
+        let fields = self.fields();
+        let idx = fields
+            .iter()
+            .position(|f| *f == self.current_field)
+            .unwrap_or(0);
+        self.current_field = fields[(idx + 1) % fields.len()];
+    }
+
+    pub fn focus_prev(&mut self) {
+        let fields = self.fields();
+        let idx = fields
+            .iter()
+            .position(|f| *f == self.current_field)
+            .unwrap_or(0);
+        self.current_field = fields[(idx + fields.len() - 1) % fields.len()];
+    }
+
+    pub fn field(&self, field: FormField) -> &TextArea<'static> {
+        match field {
+            FormField::GoalName => &self.goal_name,
+            FormField::Quantity => &self.quantity_name,
+            FormField::AllowNegative => &self.allow_negative,
+            FormField::DefaultDuration => &self.default_duration,
+            FormField::DailyTarget => &self.daily_target,
+            FormField::WeeklyTarget => &self.weekly_target,
+            FormField::RewardWeight => &self.reward_weight,
+            FormField::Commands => &self.commands,
+        }
+    }
+
+    pub fn field_mut(&mut self, field: FormField) -> &mut TextArea<'static> {
+        match field {
+            FormField::GoalName => &mut self.goal_name,
+            FormField::Quantity => &mut self.quantity_name,
+            FormField::AllowNegative => &mut self.allow_negative,
+            FormField::DefaultDuration => &mut self.default_duration,
+            FormField::DailyTarget => &mut self.daily_target,
+            FormField::WeeklyTarget => &mut self.weekly_target,
+            FormField::RewardWeight => &mut self.reward_weight,
+            FormField::Commands => &mut self.commands,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum FocusedBlock {
     #[default]
     SessionsList,
@@ -134,6 +348,35 @@ pub enum SearchResult {
     Create { name: String, is_reward: bool },
 }
 
+/// Totals shown in the end-of-day insights popup.
+#[derive(Debug, Clone, Default)]
+pub struct DayInsights {
+    pub focus_secs: i64,
+    pub sessions: usize,
+    /// Goal sessions flagged as interrupted.
+    pub interrupted: usize,
+    /// Average rating of each rated goal's sessions.
+    pub ratings: Vec<(String, f32)>,
+    pub top_goal: Option<(String, i64)>,
+    pub reward_balance_secs: i64,
+    pub targets_met: usize,
+    pub targets_total: usize,
+    /// Quantities summed per unit across goals, for the day and for the
+    /// `QUANTITY_TOTAL_DAYS` days ending with it.
+    pub quantities: Vec<(String, i64)>,
+    pub recent_quantities: Vec<(String, i64)>,
+}
+
+/// A goal's sessions within a day, for the grouped sessions list.
+#[derive(Debug, Clone)]
+pub struct GoalGroup {
+    pub goal_id: u64,
+    pub name: String,
+    /// Indices into the day's sessions, in order.
+    pub sessions: Vec<usize>,
+    pub total_secs: i64,
+}
+
 // ── Timer / Pending ──────────────────────────────────────────────────────
 
 #[derive(Debug)]
@@ -144,6 +387,13 @@ pub struct TimerState {
     pub total: u64,
     pub is_reward: bool,
     pub started_at: DateTime<Utc>,
+    /// The goal's commands weren't launched for this session.
+    pub skip_commands: bool,
+    /// Seconds spent away from the keyboard, left out of the recorded time.
+    pub idle_secs: u64,
+    /// Ask for the quantity done when the timer ends (the goal's
+    /// `prompt_quantity`, toggled with `Q`).
+    pub ask_quantity: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -151,6 +401,10 @@ pub struct PendingSession {
     pub label: String,
     pub goal_id: u64,
     pub total: u64,
+    /// Seconds actually spent when the timer was stopped before `total`.
+    pub duration_override: Option<u64>,
     pub is_reward: bool,
     pub started_at: DateTime<Utc>,
+    /// Logged with `c` rather than timed; recorded as `CHECKPOINT_SECONDS`.
+    pub is_checkpoint: bool,
 }
@@ -1,11 +1,16 @@
 use chrono::Local;
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph};
+use ratatui::widgets::{
+    Block, Borders, Gauge, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
+    ScrollbarState,
+};
 
 use crate::app::AppState;
+use crate::config::{GaugeLabel, Prefixes};
 use crate::handlers::search_results;
+use crate::notes::{notes_modified, notes_target};
 use crate::style;
 use crate::types::*;
 use crate::utils::*;
@@ -24,16 +29,36 @@ pub struct ViewItem {
 pub enum ViewItemKind {
     RunningTimer,
     Existing(SessionKind, usize),
+    /// Heading of a goal's sessions in the grouped view.
+    GoalHeader(u64),
     AddSession,
     AddReward,
 }
 
-fn build_timer_view_items(timer: &TimerState, _width: usize) -> Vec<ViewItem> {
+/// Appended to the label of sessions flagged as interrupted.
+const INTERRUPTED_MARKER: &str = " ↯";
+
+fn build_timer_view_items(
+    timer: &TimerState,
+    _width: usize,
+    compact: bool,
+    prefixes: &Prefixes,
+    interrupted: bool,
+) -> Vec<ViewItem> {
     let started_local = timer.started_at.with_timezone(&Local).format("%H:%M");
-    let info_line = format!(
-        "[*] {} ({}s left) [started {}]",
-        timer.label, timer.remaining, started_local
-    );
+    let running = &prefixes.running;
+    let marker = if interrupted { INTERRUPTED_MARKER } else { "" };
+    let info_line = if compact {
+        format!(
+            "{running} {} ({}s left){marker}",
+            timer.label, timer.remaining
+        )
+    } else {
+        format!(
+            "{running} {} ({}s left) [started {}]{marker}",
+            timer.label, timer.remaining, started_local
+        )
+    };
 
     vec![ViewItem {
         label: info_line,
@@ -52,30 +77,90 @@ fn get_formatted_session_time_range(n: &SessionView) -> String {
 }
 
 pub fn build_view_items(state: &AppState, width: usize) -> Vec<ViewItem> {
-    let mut items = Vec::new();
-    for (idx, n) in state.nodes.iter().enumerate() {
+    let prefixes = &state.config.prefixes;
+    let add = &prefixes.add;
+    let session_item = |idx: usize, n: &SessionView, indent: &str| {
         let prefix = match n.kind {
-            SessionKind::Goal => "[S]",
-            SessionKind::Reward => "[R]",
+            SessionKind::Goal => &prefixes.session,
+            SessionKind::Reward => &prefixes.reward,
         };
         let duration = (n.end_at - n.start_at) / 60;
-        let times = get_formatted_session_time_range(n);
-        let unit = goal_quantity_name(state, n.goal_id)
-            .map(|u| format!(" {u}"))
+        let meta = state.session_meta(n.goal_id, n.start_at);
+        let interrupted = if meta.interrupted {
+            INTERRUPTED_MARKER
+        } else {
+            ""
+        };
+        let stars = meta
+            .rating
+            .map(|r| format!(" {}", "★".repeat(r as usize)))
             .unwrap_or_default();
+        if state.compact_labels {
+            return ViewItem {
+                label: format!(
+                    "{indent}{prefix} {} ({duration}m){interrupted}{stars}",
+                    n.name
+                ),
+                kind: ViewItemKind::Existing(n.kind, idx),
+            };
+        }
+        let times = get_formatted_session_time_range(n);
         let qty_label = n
             .quantity
-            .map(|q| format!("{q}{unit} in "))
+            .map(|q| format!("{} in ", format_quantity_with_unit(state, n.goal_id, q)))
+            .unwrap_or_default();
+        let planned_label = meta
+            .planned_secs
+            .filter(|_| state.config.show_planned)
+            .map(|planned| format!(" / planned {}m", planned / 60))
             .unwrap_or_default();
-        items.push(ViewItem {
-            label: format!("{prefix} {} ({qty_label}{duration}m) [{times}]", n.name),
+        ViewItem {
+            label: format!(
+                "{indent}{prefix} {} ({qty_label}{duration}m{planned_label}) [{times}]{interrupted}{stars}",
+                n.name
+            ),
             kind: ViewItemKind::Existing(n.kind, idx),
-        });
+        }
+    };
+
+    let mut items = Vec::new();
+    if state.grouped_view {
+        for group in group_day_sessions_by_goal(&state.nodes) {
+            let collapsed = state.collapsed_goals.contains(&group.goal_id);
+            let marker = if collapsed { "▸" } else { "▾" };
+            let count = group.sessions.len();
+            let plural = if count == 1 { "" } else { "s" };
+            items.push(ViewItem {
+                label: format!(
+                    "{marker} {} ({count} session{plural}, {})",
+                    group.name,
+                    format_secs_short(group.total_secs)
+                ),
+                kind: ViewItemKind::GoalHeader(group.goal_id),
+            });
+            if !collapsed {
+                for idx in group.sessions {
+                    items.push(session_item(idx, &state.nodes[idx], "  "));
+                }
+            }
+        }
+    } else {
+        for (idx, n) in state.nodes.iter().enumerate() {
+            items.push(session_item(idx, n, ""));
+        }
     }
 
     if let Some(timer) = &state.timer {
         if state.current_day == Local::now().date_naive() {
-            items.extend(build_timer_view_items(timer, width));
+            items.extend(build_timer_view_items(
+                timer,
+                width,
+                state.compact_labels,
+                prefixes,
+                state
+                    .session_meta(timer.goal_id, timer.started_at.timestamp())
+                    .interrupted,
+            ));
         }
     }
 
@@ -87,7 +172,7 @@ pub fn build_view_items(state: &AppState, width: usize) -> Vec<ViewItem> {
         {
             let quantity_name = quantity_name.as_deref().unwrap_or("quantity");
             items.push(ViewItem {
-                label: format!("[+] Insert {quantity_name} for {goal_name}"),
+                label: format!("{add} Insert {quantity_name} for {goal_name}"),
                 kind: ViewItemKind::AddSession,
             });
         } else if state
@@ -97,12 +182,12 @@ pub fn build_view_items(state: &AppState, width: usize) -> Vec<ViewItem> {
             .unwrap_or(false)
         {
             items.push(ViewItem {
-                label: "[+] Receive reward".to_string(),
+                label: format!("{add} Receive reward"),
                 kind: ViewItemKind::AddReward,
             });
         } else {
             items.push(ViewItem {
-                label: "[+] Work on new goal".to_string(),
+                label: format!("{add} Work on new goal"),
                 kind: ViewItemKind::AddSession,
             });
         }
@@ -110,12 +195,42 @@ pub fn build_view_items(state: &AppState, width: usize) -> Vec<ViewItem> {
     items
 }
 
+/// What each sessions list prefix means, e.g. "[S] session • [R] reward".
+fn prefix_legend(prefixes: &Prefixes) -> String {
+    format!(
+        " {} session • {} reward • {} running • {} add ",
+        prefixes.session, prefixes.reward, prefixes.running, prefixes.add
+    )
+}
+
+/// Current local time, plus when the running timer ends, e.g.
+/// "14:05 • ends 14:30".
+fn now_indicator(state: &AppState) -> String {
+    let now = Local::now().format("%H:%M").to_string();
+    match &state.timer {
+        Some(timer) => {
+            let ends = timer.started_at + chrono::Duration::seconds(timer.total as i64);
+            format!(
+                " {now} • ends {} ",
+                ends.with_timezone(&Local).format("%H:%M")
+            )
+        }
+        None => format!(" {now} "),
+    }
+}
+
 // ── Main UI ──────────────────────────────────────────────────────────────
 
 /// Render the entire UI.
 ///
 /// `header_text` is the text shown in the header bar (e.g. "Archive: /path (open with 'o')").
 pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
+    if let Some(timer) = state.timer.as_ref().filter(|_| state.focus_view) {
+        render_focus_view(f, state, timer);
+        render_dialogs(f, state);
+        return;
+    }
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -131,11 +246,46 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
 
     let dimmed = get_dimmed_style(&state.mode);
 
-    let header = Paragraph::new(Line::from(header_text.to_string()))
+    let mut header_spans = vec![Span::raw(header_text.to_string())];
+    if !state.sparkline.trim().is_empty() {
+        header_spans.push(Span::raw(" • "));
+        let sparkline = if state.ascii {
+            ascii_sparkline(&state.sparkline)
+        } else {
+            state.sparkline.clone()
+        };
+        header_spans.push(Span::styled(
+            sparkline,
+            Style::default().fg(state.config.accent()),
+        ));
+    }
+    let break_due = state.timer.as_ref().is_some_and(|timer| {
+        !timer.is_reward
+            && state
+                .config
+                .break_reminder_mins
+                .is_some_and(|mins| timer.total - timer.remaining >= mins * 60)
+    });
+    if break_due {
+        header_spans.push(Span::raw(" • "));
+        header_spans.push(Span::styled(
+            "Consider a break",
+            Style::default().fg(style::YELLOW),
+        ));
+    }
+    if let Some(status) = &state.status {
+        header_spans.push(Span::raw(" • "));
+        header_spans.push(Span::styled(
+            status.clone(),
+            Style::default().fg(style::YELLOW),
+        ));
+    }
+    let header = Paragraph::new(Line::from(header_spans))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Success CLI")
+                .title_top(Line::from(now_indicator(state)).right_aligned())
                 .style(dimmed),
         )
         .style(dimmed);
@@ -158,6 +308,7 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
     let list_width = list_area.width.saturating_sub(4) as usize;
 
     let items = build_view_items(state, list_width);
+    let wrap_indent = state.config.wrap_indent.min(4);
 
     let list_items: Vec<ListItem> = items
         .iter()
@@ -176,7 +327,7 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
 
             let label_style = if should_highlight {
                 Style::default()
-                    .fg(style::BLUE)
+                    .fg(state.config.accent())
                     .add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
@@ -186,17 +337,9 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
             let mut lines: Vec<Line> = label_lines
                 .iter()
                 .flat_map(|line_text| {
-                    wrap_text(line_text, list_width)
+                    wrap_text_indented(line_text, list_width, wrap_indent)
                         .into_iter()
-                        .enumerate()
-                        .map(|(wrap_idx, wrapped)| {
-                            let text = if wrap_idx == 0 {
-                                wrapped
-                            } else {
-                                format!("    {}", wrapped)
-                            };
-                            Line::from(vec![Span::styled(text, label_style)])
-                        })
+                        .map(|wrapped| Line::from(vec![Span::styled(wrapped, label_style)]))
                         .collect::<Vec<Line>>()
                 })
                 .collect();
@@ -217,6 +360,12 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
                             Style::default().fg(style::GRAY_DIM),
                         )]
                     }
+                    ViewItemKind::GoalHeader(_) => {
+                        vec![Span::styled(
+                            " (Enter: collapse/expand)",
+                            Style::default().fg(style::GRAY_DIM),
+                        )]
+                    }
                     ViewItemKind::Existing(_, _) | ViewItemKind::RunningTimer => {
                         if state.focused_block == FocusedBlock::SessionsList {
                             vec![Span::styled(
@@ -236,6 +385,22 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
                 }
             }
 
+            if let ViewItemKind::Existing(_, idx) = item.kind {
+                let snippet = state
+                    .nodes
+                    .get(idx)
+                    .filter(|_| !state.compact_labels)
+                    .and_then(|n| state.note_snippets.get(&(n.goal_id, n.start_at)));
+                if let Some(snippet) = snippet {
+                    let indent = " ".repeat(wrap_indent);
+                    let width = list_width.saturating_sub(wrap_indent);
+                    lines.push(Line::from(Span::styled(
+                        format!("{indent}{}", truncate_with_ellipsis(snippet, width)),
+                        Style::default().fg(style::GRAY_DIM),
+                    )));
+                }
+            }
+
             ListItem::new(lines)
         })
         .collect();
@@ -245,7 +410,7 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
         format_day_label(state.current_day)
     );
 
-    let sessions_block = Block::default()
+    let mut sessions_block = Block::default()
         .borders(Borders::ALL)
         .title(title)
         .style(dimmed)
@@ -253,8 +418,19 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
             state.focused_block,
             FocusedBlock::SessionsList,
             &state.mode,
+            state.config.accent(),
+        ));
+
+    if state.config.prefix_legend {
+        sessions_block = sessions_block.title_bottom(Span::styled(
+            prefix_legend(&state.config.prefixes),
+            Style::default().fg(style::GRAY_DIM),
         ));
+    }
 
+    // Items wrap over several rows, so the scrollbar works in rows.
+    let item_heights: Vec<usize> = list_items.iter().map(ListItem::height).collect();
+    let list_viewport = sessions_block.inner(list_area).height;
     let list = List::new(list_items).block(sessions_block).style(dimmed);
 
     let mut stateful = ListState::default();
@@ -262,42 +438,51 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
         stateful.select(Some(state.selected.min(items.len() - 1)));
     }
     f.render_stateful_widget(list, list_area, &mut stateful);
+    let scrolled_rows = item_heights.iter().take(stateful.offset()).sum();
+    render_scrollbar(
+        f,
+        list_area,
+        item_heights.iter().sum(),
+        scrolled_rows,
+        list_viewport,
+    );
 
     if let (Some(timer), Some(gauge_area)) = (&state.timer, gauge_area) {
-        let pct = if timer.total == 0 {
-            0.0
-        } else {
-            1.0 - (timer.remaining as f64 / timer.total as f64)
-        };
-        let ratio = pct.clamp(0.0, 1.0);
-        let label = format!("{:.0}%", ratio * 100.0);
-
-        let gauge = Gauge::default()
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Timer Progress")
-                    .style(dimmed)
-                    .border_style(get_block_style(
-                        state.focused_block,
-                        FocusedBlock::SessionsList,
-                        &state.mode,
-                    )),
-            )
-            .gauge_style(Style::default().fg(style::BLUE))
-            .ratio(ratio)
-            .label(label)
-            .use_unicode(true);
-
-        f.render_widget(gauge, gauge_area);
+        render_timer_gauge(f, state, timer, gauge_area, dimmed);
     }
 
     // ── Notes panel ──
-    let notes_title = if matches!(state.mode, Mode::NotesEdit) {
-        "Notes (Esc to stop editing)"
+    let notes_name = if state.day_notes {
+        "Day notes"
     } else {
         "Notes"
     };
+    let notes_name = match notes_modified(state).and_then(|t| t.elapsed().ok()) {
+        Some(age) => format!("{notes_name} (edited {})", format_ago(age.as_secs())),
+        None => notes_name.to_string(),
+    };
+    let notes_name = match state.notes_lock {
+        Some((goal_id, day)) if !state.day_notes => {
+            let goal = state
+                .goals
+                .iter()
+                .find(|g| g.id == goal_id)
+                .map(|g| g.name.as_str())
+                .unwrap_or_default();
+            format!("{notes_name} [locked: {goal}, {}]", day.format("%Y-%m-%d"))
+        }
+        _ => notes_name,
+    };
+    let notes_name = if state.notes_dirty {
+        format!("{notes_name} *")
+    } else {
+        notes_name
+    };
+    let notes_title = if matches!(state.mode, Mode::NotesEdit) {
+        format!("{notes_name} (Ctrl+s to save, Esc to stop editing)")
+    } else {
+        notes_name
+    };
 
     let notes_block = Block::default()
         .borders(Borders::ALL)
@@ -307,19 +492,32 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
             state.focused_block,
             FocusedBlock::Notes,
             &state.mode,
+            state.config.accent(),
         ));
 
-    if selected_goal_id(state).is_some() {
+    if state.day_notes || notes_target(state).is_some() {
         let notes_inner = notes_block.inner(body_chunks[1]);
         f.render_widget(notes_block, body_chunks[1]);
 
-        if matches!(state.mode, Mode::NotesEdit) {
+        let (total_lines, position) = if matches!(state.mode, Mode::NotesEdit) {
             f.render_widget(&state.notes_textarea, notes_inner);
+            (
+                state.notes_textarea.lines().len(),
+                state.notes_textarea.cursor().0,
+            )
         } else {
-            let notes_content = state.notes_textarea.lines().join("\n");
-            let notes_para = Paragraph::new(notes_content).style(dimmed);
+            let (lines, scroll) = notes_display_lines(
+                state.notes_textarea.lines(),
+                state.config.notes_display_width,
+                state.notes_scroll as usize,
+            );
+            let notes_para = Paragraph::new(lines.join("\n"))
+                .style(dimmed)
+                .scroll((scroll.min(u16::MAX as usize) as u16, 0));
             f.render_widget(notes_para, notes_inner);
-        }
+            (lines.len(), scroll)
+        };
+        render_scrollbar(f, body_chunks[1], total_lines, position, notes_inner.height);
     } else {
         let notes_para = Paragraph::new("Select a task to view notes")
             .block(notes_block)
@@ -327,10 +525,168 @@ pub fn ui(f: &mut ratatui::Frame, state: &AppState, header_text: &str) {
         f.render_widget(notes_para, body_chunks[1]);
     }
 
+    render_dialogs(f, state);
+}
+
+fn render_dialogs(f: &mut ratatui::Frame, state: &AppState) {
     render_goal_selector_dialog(f, state);
     render_goal_form_dialog(f, state);
     render_duration_input_dialog(f, state);
     render_quantity_input_dialog(f, state);
+    render_session_summary_dialog(f, state);
+    render_insights_dialog(f, state);
+    render_goal_history_dialog(f, state);
+    render_goal_paths_dialog(f, state);
+    render_settings_dialog(f, state);
+    render_prompt_dialog(f, state);
+    render_recover_session_dialog(f, state);
+    render_confirm_reward_dialog(f, state);
+}
+
+fn render_timer_gauge(
+    f: &mut ratatui::Frame,
+    state: &AppState,
+    timer: &TimerState,
+    gauge_area: Rect,
+    dimmed: Style,
+) {
+    let pct = if timer.total == 0 {
+        0.0
+    } else {
+        1.0 - (timer.remaining as f64 / timer.total as f64)
+    };
+    let ratio = pct.clamp(0.0, 1.0);
+    let percent = format!("{:.0}%", ratio * 100.0);
+    let remaining = format!("{} remaining", format_hms(timer.remaining));
+    let label = match state.config.gauge_label {
+        GaugeLabel::Percent => percent,
+        GaugeLabel::Remaining => remaining,
+        GaugeLabel::Both => format!("{percent} • {remaining}"),
+    };
+
+    let gauge_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Timer Progress")
+        .style(dimmed)
+        .border_style(get_block_style(
+            state.focused_block,
+            FocusedBlock::SessionsList,
+            &state.mode,
+            state.config.accent(),
+        ));
+
+    if state.ascii {
+        let inner = gauge_block.inner(gauge_area);
+        let bar_width = (inner.width as usize).saturating_sub(display_width(&label) + 1);
+        let bar = Paragraph::new(Line::from(vec![
+            Span::styled(
+                ascii_bar(ratio, bar_width),
+                Style::default().fg(state.config.accent()),
+            ),
+            Span::raw(format!(" {label}")),
+        ]))
+        .block(gauge_block);
+        f.render_widget(bar, gauge_area);
+    } else {
+        let gauge = Gauge::default()
+            .block(gauge_block)
+            .gauge_style(Style::default().fg(state.config.accent()))
+            .ratio(ratio)
+            .label(label)
+            .use_unicode(true);
+        f.render_widget(gauge, gauge_area);
+    }
+}
+
+/// Full-screen countdown shown instead of the usual panels (toggled with `f`
+/// while the timer runs).
+fn render_focus_view(f: &mut ratatui::Frame, state: &AppState, timer: &TimerState) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Focus (f/Esc: leave • S: stop)")
+        .border_style(Style::default().fg(state.config.accent()));
+    let inner = block.inner(f.area());
+    f.render_widget(block, f.area());
+
+    let countdown = format_hms(timer.remaining);
+    let big = big_digits(&countdown, state.ascii);
+    let mut lines = vec![
+        Line::from(Span::styled(
+            timer.label.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::default(),
+    ];
+    if big
+        .iter()
+        .all(|row| display_width(row) <= inner.width as usize)
+    {
+        lines.extend(big.into_iter().map(|row| {
+            Line::from(Span::styled(
+                row,
+                Style::default().fg(state.config.accent()),
+            ))
+        }));
+    } else {
+        lines.push(Line::from(Span::styled(
+            countdown,
+            Style::default()
+                .fg(state.config.accent())
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+    lines.push(Line::default());
+
+    let width = inner.width.min(60);
+    let height = (lines.len() as u16 + 3).min(inner.height);
+    let area = Rect {
+        x: inner.x + (inner.width - width) / 2,
+        y: inner.y + (inner.height - height) / 2,
+        width,
+        height,
+    };
+    let text_height = area.height.saturating_sub(3);
+    let text_area = Rect {
+        height: text_height,
+        ..area
+    };
+    f.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center),
+        text_area,
+    );
+    if area.height >= 3 {
+        let gauge_area = Rect {
+            y: area.y + text_height,
+            height: 3,
+            ..area
+        };
+        render_timer_gauge(f, state, timer, gauge_area, Style::default());
+    }
+}
+
+/// Lines of the read-only notes view, wrapped at `width` columns if set, and
+/// the scroll offset that keeps raw line `scroll` at the top.
+fn notes_display_lines(
+    lines: &[String],
+    width: Option<usize>,
+    scroll: usize,
+) -> (Vec<String>, usize) {
+    let Some(width) = width.filter(|w| *w > 0) else {
+        return (lines.to_vec(), scroll);
+    };
+    let mut wrapped = Vec::new();
+    let mut wrapped_scroll = 0;
+    for (idx, line) in lines.iter().enumerate() {
+        if idx == scroll {
+            wrapped_scroll = wrapped.len();
+        }
+        if display_width(line) <= width {
+            wrapped.push(line.clone());
+        } else {
+            wrapped.extend(wrap_text(line, width));
+        }
+    }
+    (wrapped, wrapped_scroll)
 }
 
 // ── Dialogs ──────────────────────────────────────────────────────────────
@@ -372,24 +728,55 @@ fn render_labeled_form_field(
     }
 }
 
+/// Vertical scrollbar on the right border of `area`, shown only when
+/// `content_len` lines don't fit in `viewport` rows.
+fn render_scrollbar(
+    f: &mut ratatui::Frame,
+    area: Rect,
+    content_len: usize,
+    position: usize,
+    viewport: u16,
+) {
+    if content_len <= viewport as usize {
+        return;
+    }
+    let mut scrollbar_state = ScrollbarState::new(content_len)
+        .position(position)
+        .viewport_content_length(viewport as usize);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut scrollbar_state,
+    );
+}
+
 fn render_goal_selector_dialog(f: &mut ratatui::Frame, state: &AppState) {
-    if !matches!(state.mode, Mode::AddSession | Mode::AddReward) {
+    if !matches!(
+        state.mode,
+        Mode::AddSession | Mode::AddReward | Mode::AddCheckpoint | Mode::AddBackfill
+    ) {
         return;
     }
 
     let popup_area = centered_rect(80, 70, f.area());
     f.render_widget(ratatui::widgets::Clear, popup_area);
 
-    let prompt = if matches!(state.mode, Mode::AddReward) {
-        "Choose reward"
-    } else {
-        "Choose goal"
+    let prompt = match state.mode {
+        Mode::AddReward => "Choose reward",
+        Mode::AddCheckpoint => "Log checkpoint for goal",
+        Mode::AddBackfill => "Log past session for goal",
+        _ => "Choose goal",
     };
 
     let popup_block = Block::default()
         .borders(Borders::ALL)
         .title(prompt)
-        .border_style(Style::default().fg(style::BLUE));
+        .border_style(Style::default().fg(state.config.accent()));
 
     let inner = popup_block.inner(popup_area);
     f.render_widget(popup_block, popup_area);
@@ -406,9 +793,10 @@ fn render_goal_selector_dialog(f: &mut ratatui::Frame, state: &AppState) {
     render_prompted_textarea_line(f, dialog_chunks[0], "> ", &state.search_input);
 
     let results = search_results(state);
+    let label_width = dialog_chunks[1].width as usize;
     let list_items: Vec<ListItem> = results
         .iter()
-        .map(|(label, _)| ListItem::new(Line::from(label.clone())))
+        .map(|(label, _)| ListItem::new(Line::from(truncate_with_ellipsis(label, label_width))))
         .collect();
 
     let mut list_state = ListState::default();
@@ -418,15 +806,22 @@ fn render_goal_selector_dialog(f: &mut ratatui::Frame, state: &AppState) {
 
     let list = List::new(list_items).highlight_style(
         Style::default()
-            .fg(style::BLUE)
+            .fg(state.config.accent())
             .add_modifier(Modifier::BOLD),
     );
 
     f.render_stateful_widget(list, dialog_chunks[1], &mut list_state);
 
+    let sort_hint = if state.search_sort_by_total {
+        "Tab: sort by recent"
+    } else {
+        "Tab: sort by total time"
+    };
     f.render_widget(
-        Paragraph::new("Type to search • ↑↓ select • Enter pick • Esc cancel")
-            .style(Style::default().fg(style::GRAY_DIM)),
+        Paragraph::new(format!(
+            "Type to search • ↑↓ select • Enter pick • {sort_hint} • Ctrl+p pin • Esc cancel"
+        ))
+        .style(Style::default().fg(style::GRAY_DIM)),
         dialog_chunks[2],
     );
 }
@@ -448,117 +843,137 @@ fn render_goal_form_dialog(f: &mut ratatui::Frame, state: &AppState) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_style(Style::default().fg(style::BLUE));
+        .border_style(Style::default().fg(state.config.accent()));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
 
+    let fields = form.fields();
+    let mut constraints: Vec<Constraint> = fields.iter().map(|_| Constraint::Length(1)).collect();
     #[cfg(feature = "web")]
+    constraints.push(Constraint::Length(2)); // Web note
+    constraints.push(Constraint::Length(1)); // Spacer
+    constraints.push(Constraint::Min(1)); // Filler
+    constraints.push(Constraint::Length(1)); // Help text
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1), // Goal Name
-            Constraint::Length(1), // Quantity
-            Constraint::Length(1), // Commands
-            Constraint::Length(1), // Web note
-            Constraint::Length(1), // Spacer
-            Constraint::Min(1),    // Filler
-            Constraint::Length(1), // Help text
-        ])
-        .split(inner);
-    #[cfg(not(feature = "web"))]
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(1), // Goal Name
-            Constraint::Length(1), // Quantity
-            Constraint::Length(1), // Commands
-            Constraint::Length(1), // Spacer
-            Constraint::Min(1),    // Filler
-            Constraint::Length(1), // Help text
-        ])
+        .constraints(constraints)
         .split(inner);
 
+    for (idx, field) in fields.iter().enumerate() {
+        let is_active = form.current_field == *field;
+        let field_style = if is_active {
+            Style::default().fg(state.config.accent())
+        } else {
+            Style::default()
+        };
+        render_labeled_form_field(
+            f,
+            layout[idx],
+            field.label(),
+            field_style,
+            form.field(*field),
+            is_active,
+        );
+    }
+
     #[cfg(feature = "web")]
     {
         let web_note = Paragraph::new(
-            "Commands are run when starting a session so that apps you used for a certain task are always opened — only available in full version",
+            "Commands (run when starting a session so that apps you used for a certain task are always opened) are only available in the full version and are not stored here",
         )
+        .wrap(ratatui::widgets::Wrap { trim: true })
         .style(Style::default().fg(style::YELLOW));
-        f.render_widget(web_note, layout[3]);
+        f.render_widget(web_note, layout[fields.len()]);
     }
 
-    let name_prefix = "Name: ";
-    let name_style = if form.current_field == FormField::GoalName {
-        Style::default().fg(style::BLUE)
-    } else {
-        Style::default()
+    let help_text = "↑↓/Tab: navigate • Enter: create • Esc: cancel";
+    let help = Paragraph::new(help_text).style(Style::default().fg(style::GRAY_DIM));
+    f.render_widget(help, layout[layout.len() - 1]);
+}
+
+fn render_duration_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::DurationInput {
+        ref goal_name,
+        is_reward,
+        goal_id,
+    } = state.mode
+    else {
+        return;
     };
-    render_labeled_form_field(
-        f,
-        layout[0],
-        name_prefix,
-        name_style,
-        &form.goal_name,
-        form.current_field == FormField::GoalName,
-    );
 
-    let qty_prefix = "Quantity name (optional): ";
-    let qty_style = if form.current_field == FormField::Quantity {
-        Style::default().fg(style::BLUE)
+    let target_met = !is_reward && daily_target_met(state, goal_id);
+    let height = if target_met { 5 } else { 4 };
+    let area = centered_rect_fixed_height(60, height, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let title = if state.skip_commands {
+        format!(
+            "Duration for {} (e.g., 30m, 1h) • apps won't launch",
+            goal_name
+        )
     } else {
-        Style::default()
+        format!("Duration for {} (e.g., 30m, 1h)", goal_name)
     };
-    render_labeled_form_field(
-        f,
-        layout[1],
-        qty_prefix,
-        qty_style,
-        &form.quantity_name,
-        form.current_field == FormField::Quantity,
-    );
 
-    let cmd_prefix = "Commands (optional, separated by ;): ";
-    let cmd_style = if form.current_field == FormField::Commands {
-        Style::default().fg(style::BLUE)
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),                              // Input
+            Constraint::Length(if target_met { 1 } else { 0 }), // Target note
+            Constraint::Min(1),                                 // Help
+        ])
+        .split(inner);
+
+    render_prompted_textarea_line(f, layout[0], "> ", &state.duration_input);
+
+    if target_met {
+        f.render_widget(
+            Paragraph::new("Daily target already met").style(Style::default().fg(style::GRAY_DIM)),
+            layout[1],
+        );
+    }
+
+    let apps_hint = if state.skip_commands {
+        "Ctrl+n: launch apps"
     } else {
-        Style::default()
+        "Ctrl+n: skip apps"
     };
-    render_labeled_form_field(
-        f,
+    let help = if is_reward {
+        let balance = affordable_reward_secs(state, goal_id);
+        format!(
+            "Enter: start • ↑↓: ±5m • Tab: max ({}m banked) • {apps_hint} • Esc: cancel",
+            balance.max(0) / 60
+        )
+    } else {
+        format!("Enter: start • ↑↓: ±5m • {apps_hint} • Esc: cancel")
+    };
+    f.render_widget(
+        Paragraph::new(help).style(Style::default().fg(style::GRAY_DIM)),
         layout[2],
-        cmd_prefix,
-        cmd_style,
-        &form.commands,
-        form.current_field == FormField::Commands,
     );
-
-    #[cfg(feature = "web")]
-    let help_text =
-        "↑↓/Tab: navigate • Enter: create • Esc: cancel • Note: Commands only work in full version";
-    #[cfg(not(feature = "web"))]
-    let help_text = "↑↓/Tab: navigate • Enter: create • Esc: cancel";
-    let help = Paragraph::new(help_text).style(Style::default().fg(style::GRAY_DIM));
-    #[cfg(feature = "web")]
-    f.render_widget(help, layout[6]);
-    #[cfg(not(feature = "web"))]
-    f.render_widget(help, layout[5]);
 }
 
-fn render_duration_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
-    let Mode::DurationInput { ref goal_name, .. } = state.mode else {
+fn render_prompt_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::Prompt { ref title, action } = state.mode else {
         return;
     };
 
     let area = centered_rect_fixed_height(60, 4, f.area());
     f.render_widget(ratatui::widgets::Clear, area);
 
-    let title = format!("Duration for {} (e.g., 30m, 1h)", goal_name);
-
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(title)
-        .border_style(Style::default().fg(style::BLUE));
+        .title(title.as_str())
+        .border_style(Style::default().fg(state.config.accent()));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -571,14 +986,59 @@ fn render_duration_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
         ])
         .split(inner);
 
-    render_prompted_textarea_line(f, layout[0], "> ", &state.duration_input);
+    render_prompted_textarea_line(f, layout[0], "> ", &state.prompt_input);
 
     f.render_widget(
-        Paragraph::new("Enter: start • Esc: cancel").style(Style::default().fg(style::GRAY_DIM)),
+        Paragraph::new(action.help()).style(Style::default().fg(style::GRAY_DIM)),
         layout[1],
     );
 }
 
+fn render_recover_session_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::RecoverSession {
+        ref goal_name,
+        started_at,
+        ..
+    } = state.mode
+    else {
+        return;
+    };
+
+    let area = centered_rect_fixed_height(60, 5, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Interrupted session")
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Question
+            Constraint::Length(1), // Input
+            Constraint::Min(1),    // Help
+        ])
+        .split(inner);
+
+    let started = started_at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    f.render_widget(
+        Paragraph::new(format!(
+            "{goal_name} started {started} wasn't recorded. How long did it last?"
+        )),
+        layout[0],
+    );
+    render_prompted_textarea_line(f, layout[1], "> ", &state.duration_input);
+    f.render_widget(
+        Paragraph::new("Enter: log • ↑↓: ±5m • Esc: don't log")
+            .style(Style::default().fg(style::GRAY_DIM)),
+        layout[2],
+    );
+}
+
 fn render_quantity_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
     let Mode::QuantityDoneInput {
         ref goal_name,
@@ -599,7 +1059,7 @@ fn render_quantity_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
     let block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_style(Style::default().fg(style::BLUE));
+        .border_style(Style::default().fg(state.config.accent()));
 
     let inner = block.inner(area);
     f.render_widget(block, area);
@@ -619,3 +1079,438 @@ fn render_quantity_input_dialog(f: &mut ratatui::Frame, state: &AppState) {
         layout[1],
     );
 }
+
+fn render_confirm_reward_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::ConfirmReward { ref goal_name, .. } = state.mode else {
+        return;
+    };
+
+    let area = centered_rect_fixed_height(60, 5, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Start {goal_name} now?"))
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Balance
+            Constraint::Length(1), // Spacer
+            Constraint::Min(1),    // Help
+        ])
+        .split(inner);
+
+    let balance = today_reward_balance_secs(state);
+    let message = if balance > 0 {
+        format!(
+            "You have {} of reward time banked today.",
+            format_secs_short(balance)
+        )
+    } else {
+        "You have no reward time banked today.".to_string()
+    };
+    f.render_widget(Paragraph::new(message), layout[0]);
+
+    f.render_widget(
+        Paragraph::new("y: start • n/Enter/Esc: not now")
+            .style(Style::default().fg(style::GRAY_DIM)),
+        layout[2],
+    );
+}
+
+fn render_session_summary_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::SessionSummary { ref message, .. } = state.mode else {
+        return;
+    };
+
+    let area = centered_rect_fixed_height(60, 4, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Session logged")
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Message
+            Constraint::Min(1),    // Help
+        ])
+        .split(inner);
+
+    f.render_widget(Paragraph::new(message.as_str()), layout[0]);
+
+    f.render_widget(
+        Paragraph::new("Any key: dismiss").style(Style::default().fg(style::GRAY_DIM)),
+        layout[1],
+    );
+}
+
+fn render_goal_history_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::GoalHistory {
+        goal_id,
+        goal_name,
+        sessions,
+        selected,
+    } = &state.mode
+    else {
+        return;
+    };
+
+    let area = centered_rect(70, 70, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let total: i64 = sessions.iter().map(|s| s.end_at - s.start_at).sum();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "History of {goal_name}: {} sessions, {}",
+            sessions.len(),
+            format_secs_short(total)
+        ))
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // Sessions
+            Constraint::Length(1), // Help
+        ])
+        .split(inner);
+
+    if sessions.is_empty() {
+        f.render_widget(Paragraph::new("No sessions yet"), layout[0]);
+    } else {
+        let width = layout[0].width as usize;
+        let list_items: Vec<ListItem> = sessions
+            .iter()
+            .map(|s| {
+                let when = chrono::DateTime::from_timestamp(s.start_at, 0)
+                    .map(|dt| {
+                        dt.with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "????-??-?? ??:??".to_string());
+                let qty = s
+                    .quantity
+                    .map(|q| format!(" ({})", format_quantity_with_unit(state, *goal_id, q)))
+                    .unwrap_or_default();
+                let label = format!("{when}  {}{qty}", format_secs_short(s.end_at - s.start_at));
+                ListItem::new(Line::from(truncate_with_ellipsis(&label, width)))
+            })
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select(Some((*selected).min(sessions.len() - 1)));
+        let list = List::new(list_items).highlight_style(
+            Style::default()
+                .fg(state.config.accent())
+                .add_modifier(Modifier::BOLD),
+        );
+        f.render_stateful_widget(list, layout[0], &mut list_state);
+    }
+
+    f.render_widget(
+        Paragraph::new("↑↓ select • Enter: go to day • Esc/H: close")
+            .style(Style::default().fg(style::GRAY_DIM)),
+        layout[1],
+    );
+}
+
+fn render_settings_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::Settings { selected, editing } = state.mode else {
+        return;
+    };
+
+    let area = centered_rect(70, 80, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Settings")
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // Settings
+            Constraint::Length(1), // Input
+            Constraint::Length(1), // Help
+        ])
+        .split(inner);
+
+    let settings = state.settings();
+    let width = layout[0].width as usize;
+    let list_items: Vec<ListItem> = settings
+        .iter()
+        .map(|(key, value)| {
+            let value = if value.is_empty() { "none" } else { value };
+            ListItem::new(Line::from(truncate_with_ellipsis(
+                &format!("{key} = {value}"),
+                width,
+            )))
+        })
+        .collect();
+    let mut list_state = ListState::default();
+    if !settings.is_empty() {
+        list_state.select(Some(selected.min(settings.len() - 1)));
+    }
+    let list = List::new(list_items).highlight_style(
+        Style::default()
+            .fg(state.config.accent())
+            .add_modifier(Modifier::BOLD),
+    );
+    f.render_stateful_widget(list, layout[0], &mut list_state);
+
+    let help = if editing {
+        render_prompted_textarea_line(f, layout[1], "> ", &state.prompt_input);
+        "Enter: save (blank for none) • Esc: cancel"
+    } else {
+        "↑↓ select • Enter: edit • Esc/,: close"
+    };
+    f.render_widget(
+        Paragraph::new(help).style(Style::default().fg(style::GRAY_DIM)),
+        layout[2],
+    );
+}
+
+fn render_goal_paths_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    let Mode::GoalPaths { goal_name, paths } = &state.mode else {
+        return;
+    };
+
+    let height = paths.len() as u16 * 2 + 3;
+    let area = centered_rect_fixed_height(80, height, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Files of {goal_name}"))
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // Paths
+            Constraint::Length(1), // Help
+        ])
+        .split(inner);
+
+    let lines: Vec<Line> = paths
+        .iter()
+        .flat_map(|(label, path)| {
+            [
+                Line::from(Span::styled(
+                    format!("{label}:"),
+                    Style::default().fg(style::GRAY_DIM),
+                )),
+                Line::from(path.as_str()),
+            ]
+        })
+        .collect();
+    f.render_widget(
+        Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: false }),
+        layout[0],
+    );
+    f.render_widget(
+        Paragraph::new("Esc/Enter: close").style(Style::default().fg(style::GRAY_DIM)),
+        layout[1],
+    );
+}
+
+fn render_insights_dialog(f: &mut ratatui::Frame, state: &AppState) {
+    if !matches!(state.mode, Mode::Insights) {
+        return;
+    }
+
+    let insights = state.insights.clone();
+    let area = centered_rect(60, 50, f.area());
+    f.render_widget(ratatui::widgets::Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "Insights for {}",
+            format_day_label(state.current_day)
+        ))
+        .border_style(Style::default().fg(state.config.accent()));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(1),    // Stats
+            Constraint::Length(1), // Help
+        ])
+        .split(inner);
+
+    let top_goal = insights
+        .top_goal
+        .map(|(name, secs)| format!("{name} ({})", format_secs_short(secs)))
+        .unwrap_or_else(|| "-".to_string());
+    let balance = if insights.reward_balance_secs < 0 {
+        format!("-{}", format_secs_short(-insights.reward_balance_secs))
+    } else {
+        format_secs_short(insights.reward_balance_secs)
+    };
+    let targets = if insights.targets_total == 0 {
+        "no daily targets set".to_string()
+    } else {
+        format!("{}/{} met", insights.targets_met, insights.targets_total)
+    };
+    let mut lines = vec![
+        Line::from(format!(
+            "Focus time: {}",
+            format_secs_short(insights.focus_secs)
+        )),
+        Line::from(format!(
+            "Sessions: {} ({} interrupted)",
+            insights.sessions, insights.interrupted
+        )),
+        Line::from(format!("Top goal: {top_goal}")),
+        Line::from(format!("Reward balance: {balance}")),
+        Line::from(format!("Daily targets: {targets}")),
+    ];
+    if !insights.quantities.is_empty() {
+        lines.push(Line::from(format!(
+            "Quantities: {}",
+            format_quantity_totals(&insights.quantities)
+        )));
+    }
+    if !insights.recent_quantities.is_empty() {
+        lines.push(Line::from(format!(
+            "Quantities (last {QUANTITY_TOTAL_DAYS} days): {}",
+            format_quantity_totals(&insights.recent_quantities)
+        )));
+    }
+    if !insights.ratings.is_empty() {
+        let ratings = insights
+            .ratings
+            .iter()
+            .map(|(name, avg)| format!("{name} {avg:.1}★"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(Line::from(format!("Ratings: {ratings}")));
+    }
+    f.render_widget(Paragraph::new(lines), layout[0]);
+
+    f.render_widget(
+        Paragraph::new("Esc/Enter/i: close").style(Style::default().fg(style::GRAY_DIM)),
+        layout[1],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::archive_with_goal;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    /// Render the whole UI into a `width` x `height` buffer and return its
+    /// rows as text.
+    fn render(state: &AppState, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, state, "Archive: test")).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    fn contains(rows: &[String], text: &str) -> bool {
+        rows.iter().any(|row| row.contains(text))
+    }
+
+    #[test]
+    fn renders_sessions_of_the_day() {
+        let (archive, goal) = archive_with_goal("ui-sessions", "Learn Rust");
+        let start = Local::now().timestamp() - 3600;
+        successlib::add_session(
+            archive.clone(),
+            goal.id,
+            goal.name.clone(),
+            start,
+            1500,
+            false,
+            None,
+        )
+        .expect("Failed to add session");
+
+        let state = AppState::new(archive.clone());
+        let rows = render(&state, 100, 20);
+
+        assert!(contains(&rows, "Archive: test"));
+        assert!(contains(&rows, "[S] Learn Rust (25m)"));
+        assert!(contains(&rows, "[+] Work on new goal"));
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn long_goal_names_are_cut_in_the_selector() {
+        let (archive, _goal) = archive_with_goal("ui-long-name", &"x".repeat(200));
+        let mut state = AppState::new(archive.clone());
+        state.mode = Mode::AddSession;
+        let rows = render(&state, 100, 30);
+
+        let popup = centered_rect(80, 70, Rect::new(0, 0, 100, 30));
+        let right_border = (popup.x + popup.width - 1) as usize;
+        let row: Vec<char> = rows
+            .iter()
+            .find(|row| row.contains("xxx…"))
+            .expect("name should be cut with an ellipsis")
+            .chars()
+            .collect();
+        let ellipsis = row.iter().position(|&c| c == '…').unwrap();
+        assert!(ellipsis < right_border);
+        assert_eq!(row[right_border], '│');
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+
+    #[test]
+    fn renders_custom_prefixes() {
+        let (archive, goal) = archive_with_goal("ui-prefixes", "Learn Rust");
+        let start = Local::now().timestamp() - 3600;
+        successlib::add_session(
+            archive.clone(),
+            goal.id,
+            goal.name.clone(),
+            start,
+            1500,
+            false,
+            None,
+        )
+        .expect("Failed to add session");
+
+        let mut state = AppState::new(archive.clone());
+        state.config.prefixes.session = "(s)".to_string();
+        let rows = render(&state, 100, 20);
+
+        assert!(contains(&rows, "(s) Learn Rust"));
+        assert!(!contains(&rows, "[S] Learn Rust"));
+
+        let _ = std::fs::remove_dir_all(&archive);
+    }
+}
@@ -1,19 +1,34 @@
-use chrono::{Local, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Local, NaiveDate};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::text::Span;
 
 use crate::app::AppState;
+use crate::goal_meta::{GoalMeta, GoalMetaMap};
 use crate::style;
 use crate::types::*;
 use crate::ui::{build_view_items, ViewItemKind};
+use successlib::{SessionKind, SessionView};
 
 pub fn is_dialog_open(mode: &Mode) -> bool {
     matches!(
         mode,
         Mode::AddSession
             | Mode::AddReward
+            | Mode::AddCheckpoint
+            | Mode::AddBackfill
             | Mode::GoalForm
             | Mode::QuantityDoneInput { .. }
             | Mode::DurationInput { .. }
+            | Mode::SessionSummary { .. }
+            | Mode::Insights
+            | Mode::GoalHistory { .. }
+            | Mode::GoalPaths { .. }
+            | Mode::Settings { .. }
+            | Mode::Prompt { .. }
+            | Mode::ConfirmReward { .. }
+            | Mode::RecoverSession { .. }
     )
 }
 
@@ -21,10 +36,11 @@ pub fn get_block_style(
     current: FocusedBlock,
     target: FocusedBlock,
     mode: &Mode,
+    accent: ratatui::style::Color,
 ) -> ratatui::style::Style {
     use ratatui::style::Style;
     if !is_dialog_open(mode) && current == target {
-        Style::default().fg(style::BLUE)
+        Style::default().fg(accent)
     } else {
         Style::default()
     }
@@ -81,6 +97,29 @@ pub fn centered_rect_fixed_height(percent_x: u16, height: u16, r: Rect) -> Rect
         .split(popup_layout[1])[1]
 }
 
+/// Terminal columns taken by `text`, counting wide characters (e.g. emoji) as two.
+pub fn display_width(text: &str) -> usize {
+    Span::raw(text).width()
+}
+
+/// Split `word` into chunks of at most `width` columns.
+fn split_to_width(word: &str, width: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut chunk = String::new();
+    for ch in word.chars() {
+        let mut buf = [0u8; 4];
+        let ch_width = display_width(ch.encode_utf8(&mut buf));
+        if !chunk.is_empty() && display_width(&chunk) + ch_width > width {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(ch);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
 pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
     if width == 0 {
         return vec![text.to_string()];
@@ -88,36 +127,18 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
     let mut lines = Vec::new();
     let mut current = String::new();
     for word in text.split_whitespace() {
-        if current.is_empty() {
-            if word.chars().count() > width {
-                let mut chars = word.chars();
-                while chars.as_str().chars().count() > 0 {
-                    let chunk: String = chars.by_ref().take(width).collect();
-                    if chunk.is_empty() {
-                        break;
-                    }
-                    lines.push(chunk);
-                }
-            } else {
-                current = word.to_string();
-            }
-        } else if current.chars().count() + 1 + word.chars().count() > width {
-            lines.push(std::mem::take(&mut current));
-            if word.chars().count() > width {
-                let mut chars = word.chars();
-                while chars.as_str().chars().count() > 0 {
-                    let chunk: String = chars.by_ref().take(width).collect();
-                    if chunk.is_empty() {
-                        break;
-                    }
-                    lines.push(chunk);
-                }
-            } else {
-                current = word.to_string();
-            }
-        } else {
+        if !current.is_empty() && display_width(&current) + 1 + display_width(word) <= width {
             current.push(' ');
             current.push_str(word);
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        if display_width(word) > width {
+            lines.extend(split_to_width(word, width));
+        } else {
+            current = word.to_string();
         }
     }
     if !current.is_empty() {
@@ -129,6 +150,64 @@ pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
     lines
 }
 
+/// Like `wrap_text`, but continuation lines are prefixed with `indent` spaces
+/// and wrapped narrower so that they still fit in `width`.
+pub fn wrap_text_indented(text: &str, width: usize, indent: usize) -> Vec<String> {
+    let first = wrap_text(text, width);
+    if indent == 0 || first.len() <= 1 || width <= indent {
+        return first;
+    }
+    let head = first[0].clone();
+    let mut words = text.split_whitespace();
+    let first_word = words.next().unwrap_or_default();
+    let rest = if display_width(first_word) > width {
+        // The first word was split into chunks; continue after the first chunk.
+        let chunks = split_to_width(first_word, width);
+        std::iter::once(chunks[1..].concat())
+            .chain(words.map(str::to_string))
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        let head_words = head.split_whitespace().count();
+        text.split_whitespace()
+            .skip(head_words)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let prefix = " ".repeat(indent);
+    let mut lines = vec![head];
+    lines.extend(
+        wrap_text(&rest, width - indent)
+            .into_iter()
+            .map(|l| format!("{prefix}{l}")),
+    );
+    lines
+}
+
+/// Shorten `text` to at most `width` characters, ending with `…` when cut.
+pub fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for ch in text.chars() {
+        let mut buf = [0u8; 4];
+        used += display_width(ch.encode_utf8(&mut buf));
+        // Leave a column for the ellipsis; a wide character that doesn't
+        // fit is dropped whole.
+        if used > width - 1 {
+            break;
+        }
+        truncated.push(ch);
+    }
+    truncated.push('…');
+    truncated
+}
+
 pub fn parse_duration(input: &str) -> Option<u64> {
     let input = input.trim();
     if input.is_empty() {
@@ -172,6 +251,32 @@ pub fn parse_duration(input: &str) -> Option<u64> {
     }
 }
 
+/// Parse a day relative to `today`: `YYYY-MM-DD`, `YYYY-MM` (first of the
+/// month), `today`, `yesterday`, `-N` (N days ago) or a weekday name (its most
+/// recent occurrence, today included). Future days are rejected.
+pub fn parse_relative_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let day = if input == "today" {
+        today
+    } else if input == "yesterday" {
+        today.pred_opt()?
+    } else if let Some(days) = input.strip_prefix('-') {
+        let days = days.trim().parse::<u64>().ok()?;
+        today.checked_sub_days(chrono::Days::new(days))?
+    } else if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        date
+    } else if let Ok(date) = NaiveDate::parse_from_str(&format!("{input}-01"), "%Y-%m-%d") {
+        date
+    } else if let Ok(weekday) = input.parse::<chrono::Weekday>() {
+        let back =
+            (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+        today.checked_sub_days(chrono::Days::new(back as u64))?
+    } else {
+        return None;
+    };
+    (day <= today).then_some(day)
+}
+
 pub fn format_day_label(day: NaiveDate) -> String {
     let today = Local::now().date_naive();
     let base = day.format("%Y-%m-%d").to_string();
@@ -220,15 +325,605 @@ pub fn parse_optional_u32(input: &str) -> Option<u32> {
     }
 }
 
+pub fn parse_optional_i32(input: &str) -> Option<i32> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        trimmed.parse::<i32>().ok()
+    }
+}
+
+/// Parse the quantity entered for `goal_id`, accepting a sign if the goal
+/// allows negative quantities.
+pub fn parse_quantity(state: &AppState, goal_id: u64, input: &str) -> Option<u32> {
+    if state.goal_meta(goal_id).allow_negative {
+        parse_optional_i32(input).map(|q| q as u32)
+    } else {
+        parse_optional_u32(input)
+    }
+}
+
+/// A stored quantity of `goal_id` as shown to the user.
+pub fn format_quantity(state: &AppState, goal_id: u64, quantity: u32) -> String {
+    if state.goal_meta(goal_id).allow_negative {
+        (quantity as i32).to_string()
+    } else {
+        quantity.to_string()
+    }
+}
+
+/// A stored quantity with the goal's unit, e.g. "1 chapter" or "3 chapters",
+/// or just the number if the goal has no quantity name.
+pub fn format_quantity_with_unit(state: &AppState, goal_id: u64, quantity: u32) -> String {
+    let value = format_quantity(state, goal_id, quantity);
+    let Some(unit) = goal_quantity_name(state, goal_id) else {
+        return value;
+    };
+    if value.trim_start_matches('-') != "1" {
+        return format!("{value} {unit}");
+    }
+    let singular = state
+        .goal_meta(goal_id)
+        .quantity_singular
+        .unwrap_or_else(|| match unit.strip_suffix('s') {
+            Some(stem) if !stem.is_empty() && !stem.ends_with('s') => stem.to_string(),
+            _ => unit.clone(),
+        });
+    format!("{value} {singular}")
+}
+
+/// Item selected after loading a day: the last item, or the last session if
+/// `auto_select_add_row` is off so Enter can't start the add flow by accident.
+pub fn default_selection(state: &AppState) -> usize {
+    let items = build_view_items(state, 20);
+    let last = items.len().saturating_sub(1);
+    if state.config.auto_select_add_row {
+        return last;
+    }
+    items
+        .iter()
+        .rposition(|item| {
+            matches!(
+                item.kind,
+                ViewItemKind::Existing(_, _) | ViewItemKind::RunningTimer
+            )
+        })
+        .unwrap_or(last)
+}
+
 pub fn selected_goal_id(state: &AppState) -> Option<u64> {
     let items = build_view_items(state, 20);
     match items.get(state.selected).map(|v| v.kind) {
         Some(ViewItemKind::RunningTimer) => state.timer.as_ref().map(|t| t.goal_id),
         Some(ViewItemKind::Existing(_, idx)) => state.nodes.get(idx).map(|n| n.goal_id),
+        Some(ViewItemKind::GoalHeader(goal_id)) => Some(goal_id),
         _ => state.timer.as_ref().map(|t| t.goal_id),
     }
 }
 
+/// Local day of the earliest recorded session in the archive, if any.
+pub fn earliest_session_day(archive_path: &str) -> Option<NaiveDate> {
+    successlib::list_sessions_between_dates(archive_path.to_string(), None, None)
+        .unwrap_or_default()
+        .iter()
+        .map(|s| s.start_at)
+        .min()
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.with_timezone(&Local).date_naive())
+}
+
+/// Number of days shown in the header sparkline.
+pub const SPARKLINE_DAYS: i64 = 14;
+
+/// Goal minutes per day for the `SPARKLINE_DAYS` days ending at `end`,
+/// rendered as block characters scaled to the busiest day.
+pub fn goal_minutes_sparkline(archive_path: &str, end: NaiveDate) -> String {
+    let start = end - chrono::Duration::days(SPARKLINE_DAYS - 1);
+    let mut minutes = vec![0i64; SPARKLINE_DAYS as usize];
+    for s in successlib::list_sessions_between_dates(archive_path.to_string(), None, None)
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| s.kind == SessionKind::Goal)
+    {
+        let Some(day) = chrono::DateTime::from_timestamp(s.start_at, 0)
+            .map(|dt| dt.with_timezone(&Local).date_naive())
+        else {
+            continue;
+        };
+        if day < start || day > end {
+            continue;
+        }
+        minutes[(day - start).num_days() as usize] += (s.end_at - s.start_at) / 60;
+    }
+    sparkline(&minutes)
+}
+
+/// ASCII stand-ins for the sparkline's block characters, lowest first.
+const ASCII_LEVELS: [char; 8] = ['_', '.', '-', '~', '=', '+', '*', '#'];
+
+/// Replace the sparkline's block characters with ASCII.
+pub fn ascii_sparkline(sparkline: &str) -> String {
+    sparkline
+        .chars()
+        .map(|c| {
+            if ('▁'..='█').contains(&c) {
+                ASCII_LEVELS[(c as u32 - '▁' as u32) as usize]
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// A `width` wide progress bar of `#` filled to `ratio` and `-` for the rest.
+pub fn ascii_bar(ratio: f64, width: usize) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    format!("{}{}", "#".repeat(filled), "-".repeat(width - filled))
+}
+
+/// `text` drawn five rows tall with block characters (`#` when `ascii`),
+/// for a countdown like "24:59". Only digits and `:` have glyphs; anything
+/// else is left blank.
+pub fn big_digits(text: &str, ascii: bool) -> Vec<String> {
+    const GLYPHS: [[&str; 5]; 10] = [
+        ["###", "# #", "# #", "# #", "###"],
+        ["  #", "  #", "  #", "  #", "  #"],
+        ["###", "  #", "###", "#  ", "###"],
+        ["###", "  #", "###", "  #", "###"],
+        ["# #", "# #", "###", "  #", "  #"],
+        ["###", "#  ", "###", "  #", "###"],
+        ["###", "#  ", "###", "# #", "###"],
+        ["###", "  #", "  #", "  #", "  #"],
+        ["###", "# #", "###", "# #", "###"],
+        ["###", "# #", "###", "  #", "###"],
+    ];
+    const COLON: [&str; 5] = [" ", "#", " ", "#", " "];
+    const BLANK: [&str; 5] = ["   "; 5];
+    (0..5)
+        .map(|row| {
+            let line = text
+                .chars()
+                .map(|c| match c {
+                    ':' => COLON[row],
+                    _ => c
+                        .to_digit(10)
+                        .map_or(BLANK[row], |d| GLYPHS[d as usize][row]),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            if ascii {
+                line
+            } else {
+                line.replace('#', "█")
+            }
+        })
+        .collect()
+}
+
+fn sparkline(values: &[i64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if max <= 0 || v <= 0 {
+                ' '
+            } else {
+                BLOCKS[((v * 8 - 1) / max).clamp(0, 7) as usize]
+            }
+        })
+        .collect()
+}
+
+/// Lifetime recorded seconds per goal id.
+pub fn goal_total_secs(archive_path: &str) -> HashMap<u64, i64> {
+    let mut totals = HashMap::new();
+    for s in successlib::list_sessions_between_dates(archive_path.to_string(), None, None)
+        .unwrap_or_default()
+    {
+        *totals.entry(s.goal_id).or_insert(0) += s.end_at - s.start_at;
+    }
+    totals
+}
+
+/// Reward seconds still available: goal time scaled by `reward_ratio`,
+/// minus the reward time already taken, each reward scaled by its weight.
+pub fn reward_balance_secs(nodes: &[SessionView], reward_ratio: f64, meta: &GoalMetaMap) -> i64 {
+    let (goal_secs, reward_secs) = nodes.iter().fold((0f64, 0f64), |(goal, reward), n| {
+        let secs = (n.end_at - n.start_at) as f64;
+        match n.kind {
+            SessionKind::Goal => (goal + secs, reward),
+            SessionKind::Reward => {
+                let weight = meta.get(&n.goal_id).map_or(1.0, GoalMeta::reward_weight);
+                (goal, reward + secs * weight as f64)
+            }
+        }
+    });
+    (goal_secs * reward_ratio - reward_secs) as i64
+}
+
+/// Run `f` on today's sessions, reusing the loaded ones when today is viewed.
+pub fn with_today_sessions<R>(state: &AppState, f: impl FnOnce(&[SessionView]) -> R) -> R {
+    let today = Local::now().date_naive();
+    if state.current_day == today {
+        return f(&state.nodes);
+    }
+    let nodes = successlib::list_day_sessions(
+        state.archive_path.clone(),
+        today.format("%Y-%m-%d").to_string(),
+    )
+    .unwrap_or_default();
+    f(&nodes)
+}
+
+pub fn today_reward_balance_secs(state: &AppState) -> i64 {
+    with_today_sessions(state, |nodes| {
+        reward_balance_secs(nodes, state.config.reward_ratio, &state.goal_meta)
+    })
+}
+
+/// Longest session of reward `goal_id` that today's balance pays for.
+pub fn affordable_reward_secs(state: &AppState, goal_id: u64) -> i64 {
+    let balance = today_reward_balance_secs(state);
+    let weight = state.goal_meta(goal_id).reward_weight();
+    if weight > 0.0 {
+        (balance as f64 / weight as f64) as i64
+    } else {
+        balance
+    }
+}
+
+/// Seconds recorded for `goal_id` among `nodes`.
+pub fn goal_secs(nodes: &[SessionView], goal_id: u64) -> i64 {
+    nodes
+        .iter()
+        .filter(|n| n.goal_id == goal_id)
+        .map(|n| n.end_at - n.start_at)
+        .sum()
+}
+
+/// Daily target of `goal_id` in seconds, if one is set.
+fn daily_target_secs(state: &AppState, goal_id: u64) -> Option<i64> {
+    state
+        .goal_meta(goal_id)
+        .daily_target
+        .and_then(|t| parse_duration(&t))
+        .map(|secs| secs as i64)
+}
+
+/// Whether `goal_id` has a daily target that today's sessions already meet.
+pub fn daily_target_met(state: &AppState, goal_id: u64) -> bool {
+    let Some(target) = daily_target_secs(state, goal_id) else {
+        return false;
+    };
+    with_today_sessions(state, |nodes| goal_secs(nodes, goal_id)) >= target
+}
+
+/// Seconds still needed today by each goal whose daily target isn't met.
+pub fn unmet_daily_targets(state: &AppState) -> HashMap<u64, i64> {
+    let targets: Vec<(u64, i64)> = state
+        .goal_meta
+        .keys()
+        .filter_map(|&goal_id| Some((goal_id, daily_target_secs(state, goal_id)?)))
+        .collect();
+    if targets.is_empty() {
+        return HashMap::new();
+    }
+    with_today_sessions(state, |nodes| {
+        targets
+            .into_iter()
+            .map(|(goal_id, target)| (goal_id, target - goal_secs(nodes, goal_id)))
+            .filter(|(_, remaining)| *remaining > 0)
+            .collect()
+    })
+}
+
+/// A goal's weekly target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeeklyTarget {
+    /// Number of sessions, written e.g. "5x".
+    Sessions(u32),
+    /// Total time in seconds, written as a duration, e.g. "3h".
+    Secs(i64),
+}
+
+pub fn parse_weekly_target(input: &str) -> Option<WeeklyTarget> {
+    let input = input.trim();
+    if let Some(count) = input.strip_suffix(['x', 'X']) {
+        return count
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(WeeklyTarget::Sessions);
+    }
+    parse_duration(input).map(|secs| WeeklyTarget::Secs(secs as i64))
+}
+
+/// First day of the week containing `day`, per the `week_start` option.
+pub fn week_start(state: &AppState, day: NaiveDate) -> NaiveDate {
+    let first = state
+        .config
+        .week_start
+        .parse::<chrono::Weekday>()
+        .unwrap_or(chrono::Weekday::Mon);
+    let back = (7 + day.weekday().num_days_from_monday() - first.num_days_from_monday()) % 7;
+    day - chrono::Duration::days(back as i64)
+}
+
+/// Progress towards each weekly target this week, e.g. "3/5" sessions or
+/// "1h 30m/3h", and whether it is met.
+pub fn weekly_progress(state: &AppState) -> HashMap<u64, (String, bool)> {
+    let targets: Vec<(u64, WeeklyTarget)> = state
+        .goal_meta
+        .iter()
+        .filter_map(|(&goal_id, meta)| {
+            Some((
+                goal_id,
+                parse_weekly_target(meta.weekly_target.as_deref()?)?,
+            ))
+        })
+        .collect();
+    if targets.is_empty() {
+        return HashMap::new();
+    }
+    let today = Local::now().date_naive();
+    let start = week_start(state, today);
+    let sessions: Vec<SessionView> =
+        successlib::list_sessions_between_dates(state.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.kind == SessionKind::Goal)
+            .filter(|s| {
+                chrono::DateTime::from_timestamp(s.start_at, 0)
+                    .map(|dt| dt.with_timezone(&Local).date_naive())
+                    .is_some_and(|day| day >= start && day <= today)
+            })
+            .collect();
+    targets
+        .into_iter()
+        .map(|(goal_id, target)| {
+            let progress = match target {
+                WeeklyTarget::Sessions(goal) => {
+                    let done = sessions.iter().filter(|s| s.goal_id == goal_id).count();
+                    (format!("{done}/{goal}"), done >= goal as usize)
+                }
+                WeeklyTarget::Secs(goal) => {
+                    let done = goal_secs(&sessions, goal_id);
+                    (
+                        format!("{}/{}", format_secs_short(done), format_secs_short(goal)),
+                        done >= goal,
+                    )
+                }
+            };
+            (goal_id, progress)
+        })
+        .collect()
+}
+
+/// Group a day's sessions by goal, in order of each goal's first session.
+pub fn group_day_sessions_by_goal(nodes: &[SessionView]) -> Vec<GoalGroup> {
+    let mut groups: Vec<GoalGroup> = Vec::new();
+    for (idx, n) in nodes.iter().enumerate() {
+        let secs = n.end_at - n.start_at;
+        match groups.iter_mut().find(|g| g.goal_id == n.goal_id) {
+            Some(group) => {
+                group.sessions.push(idx);
+                group.total_secs += secs;
+            }
+            None => groups.push(GoalGroup {
+                goal_id: n.goal_id,
+                name: n.name.clone(),
+                sessions: vec![idx],
+                total_secs: secs,
+            }),
+        }
+    }
+    groups
+}
+
+/// Summarise the viewed day for the insights popup.
+pub fn day_insights(state: &AppState) -> DayInsights {
+    let mut per_goal: Vec<(u64, String, i64)> = Vec::new();
+    let mut ratings: Vec<(u64, String, u32, u32)> = Vec::new();
+    let mut insights = DayInsights {
+        reward_balance_secs: reward_balance_secs(
+            &state.nodes,
+            state.config.reward_ratio,
+            &state.goal_meta,
+        ),
+        ..DayInsights::default()
+    };
+    for n in state.nodes.iter().filter(|n| n.kind == SessionKind::Goal) {
+        let secs = n.end_at - n.start_at;
+        insights.focus_secs += secs;
+        insights.sessions += 1;
+        let meta = state.session_meta(n.goal_id, n.start_at);
+        if meta.interrupted {
+            insights.interrupted += 1;
+        }
+        if let Some(rating) = meta.rating {
+            match ratings.iter_mut().find(|(id, ..)| *id == n.goal_id) {
+                Some(entry) => {
+                    entry.2 += rating as u32;
+                    entry.3 += 1;
+                }
+                None => ratings.push((n.goal_id, n.name.clone(), rating as u32, 1)),
+            }
+        }
+        match per_goal.iter_mut().find(|(id, _, _)| *id == n.goal_id) {
+            Some(entry) => entry.2 += secs,
+            None => per_goal.push((n.goal_id, n.name.clone(), secs)),
+        }
+    }
+    insights.top_goal = per_goal
+        .into_iter()
+        .max_by_key(|(_, _, secs)| *secs)
+        .map(|(_, name, secs)| (name, secs));
+    insights.ratings = ratings
+        .into_iter()
+        .map(|(_, name, sum, count)| (name, sum as f32 / count as f32))
+        .collect();
+    for (goal_id, meta) in &state.goal_meta {
+        let Some(target) = meta.daily_target.as_deref().and_then(parse_duration) else {
+            continue;
+        };
+        insights.targets_total += 1;
+        if goal_secs(&state.nodes, *goal_id) >= target as i64 {
+            insights.targets_met += 1;
+        }
+    }
+    insights.quantities = quantity_totals(state, &state.nodes);
+    let start = state.current_day - chrono::Duration::days(QUANTITY_TOTAL_DAYS - 1);
+    let recent: Vec<SessionView> =
+        successlib::list_sessions_between_dates(state.archive_path.clone(), None, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| {
+                chrono::DateTime::from_timestamp(s.start_at, 0)
+                    .map(|dt| dt.with_timezone(&Local).date_naive())
+                    .is_some_and(|day| day >= start && day <= state.current_day)
+            })
+            .collect();
+    insights.recent_quantities = quantity_totals(state, &recent);
+    insights
+}
+
+/// Number of days, ending with the viewed day, summed in the insights'
+/// recent quantity totals.
+pub const QUANTITY_TOTAL_DAYS: i64 = 7;
+
+/// Quantities of `sessions` summed per unit, so goals sharing a unit such as
+/// "pages" add up. Sorted by unit.
+pub fn quantity_totals(state: &AppState, sessions: &[SessionView]) -> Vec<(String, i64)> {
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+    for s in sessions {
+        let (Some(quantity), Some(unit)) = (s.quantity, goal_quantity_name(state, s.goal_id))
+        else {
+            continue;
+        };
+        let value = if state.goal_meta(s.goal_id).allow_negative {
+            quantity as i32 as i64
+        } else {
+            quantity as i64
+        };
+        *totals.entry(unit).or_insert(0) += value;
+    }
+    totals.into_iter().collect()
+}
+
+/// Markdown summary of the viewed day: a table of its sessions with their
+/// note snippets, then the day's totals.
+pub fn day_summary_markdown(state: &AppState) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let mut out = format!("# {}\n\n", state.current_day.format("%Y-%m-%d"));
+    if state.nodes.is_empty() {
+        out.push_str("No sessions.\n");
+        return out;
+    }
+    out.push_str("| Time | Session | Duration | Quantity | Note |\n");
+    out.push_str("| --- | --- | --- | --- | --- |\n");
+    for n in &state.nodes {
+        let time = [n.start_at, n.end_at]
+            .map(|ts| {
+                chrono::DateTime::from_timestamp(ts, 0)
+                    .map(|dt| dt.with_timezone(&Local).format("%H:%M").to_string())
+                    .unwrap_or_else(|| "??:??".to_string())
+            })
+            .join("-");
+        let name = match n.kind {
+            SessionKind::Goal => n.name.clone(),
+            SessionKind::Reward => format!("{} (reward)", n.name),
+        };
+        let quantity = n
+            .quantity
+            .map(|q| format_quantity_with_unit(state, n.goal_id, q))
+            .unwrap_or_default();
+        let note = state
+            .note_snippets
+            .get(&(n.goal_id, n.start_at))
+            .map(String::as_str)
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "| {time} | {} | {} | {} | {} |\n",
+            cell(&name),
+            format_secs_short(n.end_at - n.start_at),
+            cell(&quantity),
+            cell(note)
+        ));
+    }
+
+    let insights = day_insights(state);
+    let reward_secs: i64 = state
+        .nodes
+        .iter()
+        .filter(|n| n.kind == SessionKind::Reward)
+        .map(|n| n.end_at - n.start_at)
+        .sum();
+    out.push_str("\n## Totals\n\n");
+    out.push_str(&format!(
+        "- Focus time: {} in {} sessions\n",
+        format_secs_short(insights.focus_secs),
+        insights.sessions
+    ));
+    out.push_str(&format!("- Rewards: {}\n", format_secs_short(reward_secs)));
+    for group in group_day_sessions_by_goal(&state.nodes) {
+        out.push_str(&format!(
+            "- {}: {}\n",
+            group.name,
+            format_secs_short(group.total_secs)
+        ));
+    }
+    if !insights.quantities.is_empty() {
+        out.push_str(&format!(
+            "- Quantities: {}\n",
+            format_quantity_totals(&insights.quantities)
+        ));
+    }
+    out
+}
+
+/// Format quantity totals as e.g. "pages: 312 • steps: 41000".
+pub fn format_quantity_totals(totals: &[(String, i64)]) -> String {
+    totals
+        .iter()
+        .map(|(unit, total)| format!("{unit}: {total}"))
+        .collect::<Vec<_>>()
+        .join(" • ")
+}
+
+/// Format seconds as a clock, "mm:ss" or "h:mm:ss" from one hour up.
+pub fn format_hms(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// How long ago something happened, e.g. "just now", "5m ago" or "3d ago".
+pub fn format_ago(secs: u64) -> String {
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// Format seconds as e.g. "1h 5m" or "25m".
+pub fn format_secs_short(secs: i64) -> String {
+    let mins = secs.max(0) / 60;
+    let (h, m) = (mins / 60, mins % 60);
+    if h > 0 {
+        format!("{h}h {m}m")
+    } else {
+        format!("{m}m")
+    }
+}
+
 pub fn goal_quantity_name(state: &AppState, goal_id: u64) -> Option<String> {
     state
         .goals
@@ -236,3 +931,65 @@ pub fn goal_quantity_name(state: &AppState, goal_id: u64) -> Option<String> {
         .find(|g| g.id == goal_id)
         .and_then(|g| g.quantity_name.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn relative_dates_parse_each_form() {
+        // A Wednesday.
+        let today = date("2024-05-15");
+        assert_eq!(parse_relative_date("today", today), Some(today));
+        assert_eq!(
+            parse_relative_date("Yesterday", today),
+            Some(date("2024-05-14"))
+        );
+        assert_eq!(parse_relative_date("-3", today), Some(date("2024-05-12")));
+        assert_eq!(parse_relative_date("-0", today), Some(today));
+        assert_eq!(
+            parse_relative_date("2024-01-31", today),
+            Some(date("2024-01-31"))
+        );
+        assert_eq!(
+            parse_relative_date("2024-02", today),
+            Some(date("2024-02-01"))
+        );
+        assert_eq!(
+            parse_relative_date("monday", today),
+            Some(date("2024-05-13"))
+        );
+        assert_eq!(parse_relative_date("thu", today), Some(date("2024-05-09")));
+        assert_eq!(parse_relative_date("wednesday", today), Some(today));
+        assert_eq!(parse_relative_date("someday", today), None);
+    }
+
+    #[test]
+    fn truncation_fits_the_width() {
+        assert_eq!(truncate_with_ellipsis("short", 10), "short");
+        assert_eq!(truncate_with_ellipsis("abcdef", 4), "abc…");
+        assert_eq!(truncate_with_ellipsis("abcdef", 1), "…");
+        assert_eq!(truncate_with_ellipsis("abcdef", 0), "");
+    }
+
+    #[test]
+    fn truncation_keeps_wide_characters_whole() {
+        // Each character is two columns wide.
+        assert_eq!(truncate_with_ellipsis("日本語", 6), "日本語");
+        assert_eq!(truncate_with_ellipsis("日本語", 5), "日本…");
+        assert_eq!(truncate_with_ellipsis("日本語", 4), "日…");
+        assert_eq!(truncate_with_ellipsis("日本語", 2), "…");
+        assert!(display_width(&truncate_with_ellipsis("日本語", 3)) <= 3);
+    }
+
+    #[test]
+    fn relative_dates_reject_the_future() {
+        let today = date("2024-05-15");
+        assert_eq!(parse_relative_date("2024-05-16", today), None);
+        assert_eq!(parse_relative_date("2024-06", today), None);
+    }
+}
@@ -111,10 +111,7 @@ fn seed_if_empty(state: &mut AppState) {
         archive.clone(),
         "Learn Rust".to_string(),
         false,
-        vec![
-            "code .".to_string(),
-            "open https://doc.rust-lang.org".to_string(),
-        ],
+        vec![],
         Some("chapters".to_string()),
     )
     .expect("Failed to add seed goal");